@@ -5,7 +5,14 @@ __author__ = "Portfolio Site Tools"
 __description__ = "Convert CSS styles to Rust stylist format for Yew applications"
 
 from .converter import CssToRustConverter
+from .diagnostics import Diagnostic
 from .generator import RustGenerator
-from .parser import CssParser
+from .parser import CssParser, ScssParser
 
-__all__ = ["CssToRustConverter", "CssParser", "RustGenerator"]
+__all__ = [
+    "CssToRustConverter",
+    "CssParser",
+    "ScssParser",
+    "RustGenerator",
+    "Diagnostic",
+]
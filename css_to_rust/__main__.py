@@ -12,6 +12,8 @@ from rich.syntax import Syntax
 from rich.table import Table
 
 from .converter import CssToRustConverter
+from .generator import STYLE_ERRORS, STYLE_MACROS
+from .targets import TARGETS
 
 console = Console()
 
@@ -47,25 +49,98 @@ def main():
 @click.option(
     "--no-variants", is_flag=True, default=False, help="Disable variant extraction"
 )
+@click.option(
+    "--variant-enums",
+    is_flag=True,
+    default=False,
+    help="Generate variant enums and one function per component",
+)
+@click.option(
+    "--emit-components",
+    is_flag=True,
+    default=False,
+    help="Generate a Yew function component per style function",
+)
+@click.option(
+    "--target",
+    type=click.Choice(list(TARGETS)),
+    default="yew",
+    show_default=True,
+    help="Framework to generate styles for",
+)
 @click.option(
     "--utilities", is_flag=True, default=False, help="Include utility functions"
 )
+@click.option(
+    "--style-macro",
+    type=click.Choice(list(STYLE_MACROS)),
+    default=None,
+    help="Build styles with a compile-time checked stylist macro",
+)
+@click.option(
+    "--style-errors",
+    type=click.Choice(STYLE_ERRORS),
+    default="panic",
+    show_default=True,
+    help="Panic on invalid CSS, return a Result, or generate both (try_ functions)",
+)
+@click.option(
+    "--cache-styles",
+    is_flag=True,
+    default=False,
+    help="Build each style once per thread instead of on every call",
+)
 @click.option(
     "--analyze",
     is_flag=True,
     default=False,
     help="Show analysis of CSS before conversion",
 )
+@click.option(
+    "--scss-variables",
+    is_flag=True,
+    default=False,
+    help="Use SCSS $variables as value mapping candidates",
+)
+@click.option(
+    "--copy-fonts",
+    is_flag=True,
+    default=False,
+    help="Copy @font-face files into the output directory",
+)
+@click.option(
+    "--css-modules",
+    is_flag=True,
+    default=False,
+    help="Write a .module.css file with hashed class names and class name constants",
+)
+@click.option(
+    "--templates",
+    "template_dir",
+    type=click.Path(exists=True, file_okay=False),
+    default=None,
+    help="Directory of Jinja2 templates overriding the packaged ones",
+)
 def convert(
     input_path: str,
     output_path: Optional[str],
     config_path: Optional[str],
     component: bool,
     no_variants: bool,
+    variant_enums: bool,
+    emit_components: bool,
+    target: str,
     utilities: bool,
+    style_macro: Optional[str],
+    style_errors: str,
+    cache_styles: bool,
     analyze: bool,
+    scss_variables: bool,
+    copy_fonts: bool,
+    css_modules: bool,
+    template_dir: Optional[str],
 ):
-    """Convert CSS file(s) to Rust stylist format."""
+    """Convert CSS or SCSS file(s) to Rust stylist format."""
 
     # Initialize converter
     try:
@@ -89,9 +164,24 @@ def convert(
     options = {
         "group_by_component": component,
         "extract_variants": not no_variants,
+        "variant_enums": variant_enums,
+        "emit_components": emit_components,
+        "target": target,
         "include_utilities": utilities,
+        "style_macro": style_macro,
+        "style_errors": style_errors,
+        "cache_styles": cache_styles,
+        "scss_variables": scss_variables,
+        "copy_fonts": copy_fonts,
+        "css_modules": css_modules,
+        "templates": template_dir,
     }
 
+    unsupported = converter.unsupported_options(**options)
+    if unsupported:
+        flags = ", ".join(f"--{name.replace('_', '-')}" for name in unsupported)
+        raise click.UsageError(f"{flags} can't be used with --target {target}")
+
     # Show analysis if requested
     if analyze:
         if is_directory:
@@ -115,7 +205,7 @@ def convert(
                 result = converter.convert_file(input_path, output_path, **options)
 
         # Show results
-        _show_conversion_results(result, output_path, is_directory)
+        _show_conversion_results(result, output_path, is_directory, target)
 
     except Exception as e:
         console.print(f"[red]Conversion failed: {e}[/red]")
@@ -146,14 +236,15 @@ def validate(css_file: str):
         with open(css_file, "r", encoding="utf-8") as f:
             css_content = f.read()
 
-        warnings = converter.validate_css(css_content)
+        diagnostics = converter.validate_css(css_content, css_file)
 
-        if not warnings:
+        if not diagnostics:
             console.print("[green]✓ CSS file is valid for conversion[/green]")
         else:
-            console.print(f"[yellow]Found {len(warnings)} potential issues:[/yellow]")
-            for warning in warnings:
-                console.print(f"[yellow]  • {warning}[/yellow]")
+            console.print(
+                f"[yellow]Found {len(diagnostics)} potential issues:[/yellow]"
+            )
+            _print_diagnostics(diagnostics, css_content)
 
     except Exception as e:
         console.print(f"[red]Validation failed: {e}[/red]")
@@ -166,15 +257,27 @@ def validate(css_file: str):
 @click.option(
     "--no-variants", is_flag=True, default=False, help="Disable variant extraction"
 )
-def preview(css_content: str, component: bool, no_variants: bool):
+@click.option(
+    "--variant-enums",
+    is_flag=True,
+    default=False,
+    help="Generate variant enums and one function per component",
+)
+def preview(
+    css_content: str, component: bool, no_variants: bool, variant_enums: bool
+):
     """Preview Rust conversion of CSS string."""
 
     try:
         converter = CssToRustConverter()
 
-        options = {"extract_variants": not no_variants}
+        options = {
+            "extract_variants": not no_variants,
+            "variant_enums": variant_enums,
+        }
 
         functions = converter.convert_string(css_content, **options)
+        _print_diagnostics(converter.parser.diagnostics, css_content)
 
         if not functions:
             console.print("[yellow]No functions generated from CSS[/yellow]")
@@ -248,6 +351,11 @@ def _show_analysis(converter: CssToRustConverter, css_file: str):
     for component, rule_count in stats["components"].items():
         analysis_content += f"• {component}: {rule_count} rules\n"
 
+    if stats["layers"]:
+        analysis_content += "\n[bold]Cascade Layers:[/bold]\n"
+        for layer, rule_count in stats["layers"].items():
+            analysis_content += f"• {layer}: {rule_count} rules\n"
+
     panel = Panel(
         analysis_content.strip(),
         title=f"Analysis: {Path(css_file).name}",
@@ -257,7 +365,9 @@ def _show_analysis(converter: CssToRustConverter, css_file: str):
     console.print(panel)
 
 
-def _show_conversion_results(result, output_path: str, is_directory: bool):
+def _show_conversion_results(
+    result, output_path: str, is_directory: bool, target: str = "yew"
+):
     """Show conversion results."""
 
     if is_directory:
@@ -293,23 +403,65 @@ def _show_conversion_results(result, output_path: str, is_directory: bool):
         console.print(table)
 
     else:
-        # Single file conversion results
-        if result["type"] == "single_file":
-            console.print("\n[green]✓ Converted successfully[/green]")
-            console.print(f"[blue]Output file: {result['output']}[/blue]")
-            console.print(f"Functions generated: {len(result['functions'])}")
-
-            if result["keyframes"] > 0:
-                console.print(f"Keyframe animations: {result['keyframes']}")
-
-        elif result["type"] == "component_structure":
-            console.print("\n[green]✓ Created component structure[/green]")
-            console.print(f"[blue]Output directory: {result['output']}[/blue]")
-            console.print(f"Components: {len(result['components'])}")
-            console.print(f"Total functions: {result['functions']}")
-
-            if result["keyframes"] > 0:
-                console.print(f"Keyframe animations: {result['keyframes']}")
+        _show_file_results(result, target)
+
+
+def _show_file_results(result, target: str = "yew"):
+    """Show results of converting a single file for the framework ``target``."""
+    if result["type"] == "single_file":
+        console.print("\n[green]✓ Converted successfully[/green]")
+        console.print(f"[blue]Output file: {result['output']}[/blue]")
+        console.print(f"Functions generated: {len(result['functions'])}")
+
+        if result["keyframes"] > 0:
+            console.print(f"Keyframe animations: {result['keyframes']}")
+
+    elif result["type"] == "component_structure":
+        console.print("\n[green]✓ Created component structure[/green]")
+        console.print(f"[blue]Output directory: {result['output']}[/blue]")
+        console.print(f"Components: {len(result['components'])}")
+        console.print(f"Total functions: {result['functions']}")
+
+        if result["keyframes"] > 0:
+            console.print(f"Keyframe animations: {result['keyframes']}")
+
+    elif result["type"] == "css_module":
+        console.print("\n[green]✓ Created CSS module[/green]")
+        console.print(f"[blue]Output file: {result['output']}[/blue]")
+        console.print(f"[blue]Stylesheet: {result['stylesheet']}[/blue]")
+        console.print(f"Class names: {len(result['classes'])}")
+
+    if "theme" in result.get("modules", []):
+        console.print("Theme tokens: theme::Theme")
+
+    if result.get("global_styles"):
+        console.print(f"Global styles: global::{TARGETS[target].global_styles}")
+
+    if result.get("mapping_candidates"):
+        console.print(
+            "SCSS variables offered as mapping candidates: "
+            f"{len(result['mapping_candidates'])} (use --scss-variables)"
+        )
+
+    _print_diagnostics(result.get("diagnostics", []))
+
+
+def _print_diagnostics(diagnostics, source: Optional[str] = None):
+    """Print diagnostics rustc-style, with snippets of the offending source."""
+    sources = {}
+    for diagnostic in diagnostics:
+        text = source
+        span_file = diagnostic.span.file if diagnostic.span else None
+        if text is None and span_file:
+            if span_file not in sources:
+                try:
+                    sources[span_file] = Path(span_file).read_text(encoding="utf-8")
+                except OSError:
+                    sources[span_file] = None
+            text = sources[span_file]
+
+        style = "red" if diagnostic.severity == "error" else "yellow"
+        console.print(f"\n{diagnostic.render(text)}", style=style, markup=False)
 
 
 if __name__ == "__main__":
@@ -1,12 +1,32 @@
 """Main CSS to Rust converter class."""
 
 import os
+import re
+import shutil
+import textwrap
+from dataclasses import replace
 from pathlib import Path
-from typing import Any, Dict, List, Optional
+from typing import Any, Dict, List, Optional, Tuple
 
+from .diagnostics import Diagnostic, SourceSpan
 from .generator import RustGenerator
 from .mappings import ValueMappings
-from .parser import CssKeyframe, CssParser, CssRule
+from .parser import (
+    REMOTE_URL_RE,
+    CssFontFace,
+    CssKeyframe,
+    CssParser,
+    CssRule,
+    CssVariantSet,
+    ScssParser,
+)
+from .targets import TARGETS, get_target
+from .utils import merge_use_statements, split_rust_items
+
+_URL_RE = re.compile(r"url\(\s*([\"']?)([^\"')]+)\1\s*\)")
+
+# Options shaping stylist style functions, which other targets don't build
+STYLIST_OPTIONS = ("variant_enums", "style_macro", "cache_styles", "style_errors")
 
 
 class CssToRustConverter:
@@ -16,38 +36,245 @@ class CssToRustConverter:
         """Initialize the converter with optional configuration."""
         self.mappings = ValueMappings(config_path)
         self.parser = CssParser()
+        self.scss_parser = ScssParser()
         self.generator = RustGenerator(self.mappings)
 
     def convert_file(
         self, input_path: str, output_path: str, **options
     ) -> Dict[str, Any]:
-        """Convert a single CSS file to Rust."""
-        # Read CSS file
+        """Convert a single CSS or SCSS file to Rust."""
+        # Parse CSS, along with the local files it imports
+        parser = self._get_parser(input_path)
         try:
-            with open(input_path, "r", encoding="utf-8") as f:
-                css_content = f.read()
+            rules = parser.parse_file(input_path)
         except FileNotFoundError:
             raise FileNotFoundError(f"CSS file not found: {input_path}")
-        except Exception as e:
+        except (OSError, UnicodeDecodeError) as e:
             raise Exception(f"Error reading CSS file {input_path}: {e}")
+        keyframes = parser.keyframes
+        diagnostics = list(parser.diagnostics)
+        self._configure_generator(**options)
+
+        # Offer SCSS $variables as mapping candidates
+        is_scss = isinstance(parser, ScssParser)
+        if is_scss:
+            self.mappings.add_candidates(parser.variables)
+        self.mappings.use_candidates = is_scss and options.get("scss_variables", False)
+
+        if options.get("css_modules", False):
+            result = self._convert_css_module(
+                parser, rules, input_path, output_path, diagnostics, **options
+            )
+            result["diagnostics"] = diagnostics
+            return result
 
-        # Parse CSS
-        rules = self.parser.parse(css_content)
-        keyframes = self.parser.keyframes
+        modules, rules = self._generate_modules(
+            parser, rules, input_path, output_path, diagnostics, **options
+        )
 
         # Group rules if requested
         if options.get("group_by_component", False):
             components = self.parser.group_rules_by_component(rules)
-            return self._convert_components(
-                components, keyframes, output_path, **options
+            result = self._convert_components(
+                components, keyframes, output_path, modules, diagnostics, **options
             )
         else:
-            return self._convert_single_file(rules, keyframes, output_path, **options)
+            result = self._convert_single_file(
+                rules, keyframes, output_path, modules, diagnostics, **options
+            )
+
+        if is_scss:
+            result["mapping_candidates"] = self.mappings.get_candidates()
+        result["diagnostics"] = diagnostics
+
+        return result
+
+    def unsupported_options(self, **options) -> List[str]:
+        """Get the options given a value that the selected target can't apply."""
+        target = get_target(options.get("target", "yew"))
+        names = [] if target.uses_stylist else list(STYLIST_OPTIONS)
+        if target.name != "yew":
+            names.insert(0, "emit_components")
+
+        defaults = self.get_conversion_options()
+        return [
+            name
+            for name in names
+            if options.get(name, defaults[name]["default"]) != defaults[name]["default"]
+        ]
+
+    def _configure_generator(self, **options):
+        """Apply generation options to the shared generator.
+
+        Raises ``ValueError`` for options the target can't apply.
+        """
+        unsupported = self.unsupported_options(**options)
+        if unsupported:
+            raise ValueError(
+                f"{', '.join(unsupported)} can't be used with the "
+                f"{options['target']} target"
+            )
+        self.generator.style_macro = options.get("style_macro")
+        self.generator.cache_styles = options.get("cache_styles", False)
+        self.generator.style_errors = options.get("style_errors", "panic")
+        self.generator.target = get_target(options.get("target", "yew"))
+        self.generator.use_templates(options.get("templates"))
+
+    def _generate_modules(
+        self,
+        parser: CssParser,
+        rules: List[CssRule],
+        input_path: str,
+        output_path: str,
+        diagnostics: List[Diagnostic],
+        **options,
+    ) -> Tuple[Dict[str, str], List[CssRule]]:
+        """Generate the theme and global modules, returning the rules left.
+
+        Theme tokens get a typed ``theme`` module. Global selectors (body,
+        a:visited) and @font-face rules can't be scoped to a class, so they go
+        to the ``global`` module, which also states the cascade layer order.
+        """
+        modules = {}
+
+        themes, rules = parser.split_theme_tokens(rules)
+        if themes:
+            modules["theme"] = self.generator.generate_theme_module(themes)
+
+        global_rules, rules = parser.split_global_rules(rules)
+        font_faces = parser.font_faces
+        if font_faces and options.get("copy_fonts", False):
+            output_dir = Path(output_path)
+            if not options.get("group_by_component", False):
+                output_dir = output_dir.parent
+            font_faces = self._copy_fonts(
+                font_faces, input_path, output_dir / "fonts", diagnostics
+            )
+        if global_rules or font_faces or parser.layers:
+            modules["global"] = self.generator.generate_global_module(
+                global_rules, font_faces, parser.layers
+            )
+
+        return modules, rules
+
+    def _convert_css_module(
+        self,
+        parser: CssParser,
+        rules: List[CssRule],
+        input_path: str,
+        output_path: str,
+        diagnostics: List[Diagnostic],
+        **options,
+    ) -> Dict[str, Any]:
+        """Write a plain stylesheet with hashed class names and its Rust module.
+
+        Theme tokens and global rules stay in the stylesheet as they are, so
+        it is written whole next to the module, which embeds it, as
+        ``<stem>.module.css``. Raises ``ValueError`` rather than overwrite the
+        input file.
+        """
+        rust_path = Path(output_path)
+        css_path = rust_path.with_name(f"{rust_path.stem}.module.css")
+        source = Path(input_path).resolve()
+        for path in (rust_path, css_path):
+            if path.resolve() == source:
+                raise ValueError(f"Output {path} would overwrite the input file")
+        rust_path.parent.mkdir(parents=True, exist_ok=True)
+
+        font_faces = parser.font_faces
+        if font_faces and options.get("copy_fonts", False):
+            font_faces = self._copy_fonts(
+                font_faces, input_path, css_path.parent / "fonts", diagnostics
+            )
+
+        rules, classes = self.generator.hash_class_names(rules, css_path.name)
+        stylesheet = self.generator.generate_stylesheet(
+            rules, parser.keyframes, font_faces, parser.layers
+        )
+        css_path.write_text(stylesheet, encoding="utf-8")
+        rust_path.write_text(
+            self.generator.generate_css_module(classes, css_path.name),
+            encoding="utf-8",
+        )
+
+        return {
+            "type": "css_module",
+            "output": str(rust_path),
+            "stylesheet": str(css_path),
+            "classes": classes,
+            "keyframes": len(parser.keyframes),
+        }
+
+    def _copy_fonts(
+        self,
+        font_faces: List[CssFontFace],
+        input_path: str,
+        font_dir: Path,
+        diagnostics: List[Diagnostic],
+    ) -> List[CssFontFace]:
+        """Copy local font files into ``font_dir``, rewriting their ``url()``s.
+
+        Fonts are looked up relative to the stylesheet declaring them, which
+        may be an imported one.
+        """
+        copied = []
+        for font_face in font_faces:
+            span = font_face.span
+            base_dir = Path(span.file if span and span.file else input_path).parent
+
+            declarations = []
+            for declaration in font_face.declarations:
+                if declaration.name == "src":
+                    value = _URL_RE.sub(
+                        lambda match: self._copy_font(
+                            match.group(2), base_dir, font_dir, span, diagnostics
+                        ),
+                        declaration.value,
+                    )
+                    declaration = replace(declaration, value=value)
+                declarations.append(declaration)
+
+            copied.append(CssFontFace(declarations, span))
+
+        return copied
+
+    def _copy_font(
+        self,
+        url: str,
+        base_dir: Path,
+        font_dir: Path,
+        span: Optional[SourceSpan],
+        diagnostics: List[Diagnostic],
+    ) -> str:
+        """Copy one font file, returning the rewritten ``url()``."""
+        if REMOTE_URL_RE.match(url):
+            return f'url("{url}")'
+
+        # Keep query strings and fragments such as ``font.eot?#iefix``
+        file_part = re.split(r"[?#]", url, 1)[0]
+        source = base_dir / file_part
+        if not source.is_file():
+            diagnostics.append(
+                Diagnostic(
+                    "warning", "font-not-found", f"Font file not found: {url}", span
+                )
+            )
+            return f'url("{url}")'
+
+        font_dir.mkdir(parents=True, exist_ok=True)
+        shutil.copyfile(source, font_dir / source.name)
+        return f'url("fonts/{source.name}{url[len(file_part):]}")'
+
+    def _get_parser(self, input_path: str) -> CssParser:
+        """Pick the parser for a stylesheet based on its extension."""
+        if Path(input_path).suffix.lower() == ".scss":
+            return self.scss_parser
+        return self.parser
 
     def convert_directory(
         self, input_dir: str, output_dir: str, **options
     ) -> Dict[str, Any]:
-        """Convert all CSS files in a directory."""
+        """Convert all CSS and SCSS files in a directory."""
         input_path = Path(input_dir)
         output_path = Path(output_dir)
 
@@ -58,7 +285,12 @@ class CssToRustConverter:
         output_path.mkdir(parents=True, exist_ok=True)
 
         results = {}
-        css_files = list(input_path.glob("*.css"))
+        # SCSS partials (``_name.scss``) are only meant to be imported
+        css_files = list(input_path.glob("*.css")) + [
+            path
+            for path in input_path.glob("*.scss")
+            if not path.name.startswith("_")
+        ]
 
         if not css_files:
             print(f"No CSS files found in {input_dir}")
@@ -81,36 +313,13 @@ class CssToRustConverter:
         rules: List[CssRule],
         keyframes: List[CssKeyframe],
         output_path: str,
+        modules: Optional[Dict[str, str]] = None,
+        diagnostics: Optional[List[Diagnostic]] = None,
         **options,
     ) -> Dict[str, Any]:
         """Convert rules to a single Rust file."""
-        functions = {}
-
         # Generate functions from rules
-        if options.get("extract_variants", True):
-            variants = self.parser.extract_variants(rules)
-            for variant_name, variant_rules in variants.items():
-                function_name = self.parser.get_function_name_from_selector(
-                    variant_name
-                )
-                functions[function_name] = self.generator.generate_style_function(
-                    function_name, variant_rules
-                )
-        else:
-            # Group by selector
-            grouped_rules = {}
-            for rule in rules:
-                func_name = self.parser.get_function_name_from_selector(
-                    rule.selector, rule.pseudo_selector
-                )
-                if func_name not in grouped_rules:
-                    grouped_rules[func_name] = []
-                grouped_rules[func_name].append(rule)
-
-            for func_name, func_rules in grouped_rules.items():
-                functions[func_name] = self.generator.generate_style_function(
-                    func_name, func_rules
-                )
+        functions = self._generate_style_functions(rules, diagnostics, **options)
 
         # Generate keyframe functions
         if keyframes:
@@ -123,13 +332,15 @@ class CssToRustConverter:
             functions.update(utilities)
 
         # Write single file
-        self._write_single_rust_file(output_path, functions)
+        self._write_single_rust_file(output_path, functions, modules)
 
         return {
             "type": "single_file",
             "output": output_path,
             "functions": list(functions.keys()),
             "keyframes": len(keyframes),
+            "modules": list(modules or {}),
+            "global_styles": "global" in (modules or {}),
         }
 
     def _convert_components(
@@ -137,6 +348,8 @@ class CssToRustConverter:
         components: Dict[str, List[CssRule]],
         keyframes: List[CssKeyframe],
         output_path: str,
+        modules: Optional[Dict[str, str]] = None,
+        diagnostics: Optional[List[Diagnostic]] = None,
         **options,
     ) -> Dict[str, Any]:
         """Convert rules grouped by components."""
@@ -147,35 +360,9 @@ class CssToRustConverter:
 
         # Process each component
         for component_name, component_rules in components.items():
-            functions = {}
-
-            # Extract variants for this component
-            if options.get("extract_variants", True):
-                variants = self.parser.extract_variants(component_rules)
-                for variant_name, variant_rules in variants.items():
-                    function_name = self.parser.get_function_name_from_selector(
-                        variant_name
-                    )
-                    functions[function_name] = self.generator.generate_style_function(
-                        function_name, variant_rules
-                    )
-            else:
-                # Group by selector within component
-                grouped_rules = {}
-                for rule in component_rules:
-                    func_name = self.parser.get_function_name_from_selector(
-                        rule.selector, rule.pseudo_selector
-                    )
-                    if func_name not in grouped_rules:
-                        grouped_rules[func_name] = []
-                    grouped_rules[func_name].append(rule)
-
-                for func_name, func_rules in grouped_rules.items():
-                    functions[func_name] = self.generator.generate_style_function(
-                        func_name, func_rules
-                    )
-
-            component_functions[component_name] = functions
+            component_functions[component_name] = self._generate_style_functions(
+                component_rules, diagnostics, **options
+            )
 
         # Add keyframes to appropriate component or create separate module
         if keyframes:
@@ -191,7 +378,9 @@ class CssToRustConverter:
 
         # Create file structure
         created_components = self.generator.create_file_structure(
-            str(output_dir), component_functions
+            str(output_dir),
+            component_functions,
+            modules,
         )
 
         return {
@@ -200,25 +389,137 @@ class CssToRustConverter:
             "components": created_components,
             "functions": sum(len(funcs) for funcs in component_functions.values()),
             "keyframes": len(keyframes),
+            "modules": list(modules or {}),
+            "global_styles": "global" in (modules or {}),
         }
 
-    def _write_single_rust_file(self, output_path: str, functions: Dict[str, str]):
-        """Write all functions to a single Rust file."""
-        file_content = "//! Generated CSS styles\n\nuse stylist::Style;\n\n"
+    def _generate_style_functions(
+        self,
+        rules: List[CssRule],
+        diagnostics: Optional[List[Diagnostic]] = None,
+        **options,
+    ) -> Dict[str, str]:
+        """Generate style functions for rules, by variant or by selector.
 
-        for function_name, function_code in functions.items():
-            # Extract just the function part
-            function_lines = function_code.split("\n")
-            function_start = -1
+        With ``emit_components``, each function is followed by a Yew component
+        applying it, whose props select the variants. Parameters that variant
+        functions can't take are reported to ``diagnostics``.
+        """
+        functions = {}
+        emit_components = options.get("emit_components", False)
+
+        # Variant functions compose stylist styles at runtime
+        if options.get("variant_enums", False) or emit_components:
+            variant_sets, rules = self.parser.extract_variant_sets(rules)
+            for variant_set in variant_sets:
+                name = variant_set.name
+                if diagnostics is not None:
+                    diagnostics += self._variant_param_diagnostics(variant_set)
+                functions[name] = self.generator.generate_variant_function(variant_set)
+                if emit_components:
+                    functions[f"{name}_component"] = self.generator.generate_component(
+                        name, variant_set=variant_set
+                    )
 
-            for i, line in enumerate(function_lines):
-                if line.startswith("pub fn "):
-                    function_start = i
-                    break
+        grouped_rules = self._group_function_rules(rules, **options)
+        for function_name, function_rules in grouped_rules.items():
+            functions[function_name] = self.generator.generate_style_function(
+                function_name, function_rules
+            )
+            if emit_components:
+                component = self.generator.generate_component(
+                    function_name, function_rules
+                )
+                functions[f"{function_name}_component"] = component
+
+        return functions
+
+    def _variant_param_diagnostics(
+        self, variant_set: CssVariantSet
+    ) -> List[Diagnostic]:
+        """Report the parameters of a variant set, which keep their CSS value.
+
+        Variant functions take only their enums, so values annotated with
+        ``@param`` or listed under ``parameters`` stay as written.
+        """
+        rules = list(variant_set.base)
+        for variants in variant_set.axes.values():
+            for variant_rules in variants.values():
+                rules += variant_rules
+
+        diagnostics = []
+        for rule in rules:
+            for declaration in rule.declarations:
+                if declaration.param is not None:
+                    name = declaration.param.name
+                elif declaration.name in self.mappings.parameters:
+                    name = declaration.name
+                else:
+                    continue
+                diagnostics.append(
+                    Diagnostic(
+                        "warning",
+                        "variant-param",
+                        f"Parameter {name} of {rule.full_selector} is not "
+                        f"supported by the {variant_set.name} variant function; "
+                        f"its value {declaration.value} is used as written",
+                        declaration.span,
+                    )
+                )
+        return diagnostics
+
+    def _group_function_rules(
+        self, rules: List[CssRule], **options
+    ) -> Dict[str, List[CssRule]]:
+        """Group rules by the style function they go into."""
+        grouped_rules: Dict[str, List[CssRule]] = {}
+        if options.get("extract_variants", True):
+            variants = self.parser.extract_variants(rules)
+            for variant_name, variant_rules in variants.items():
+                function_name = self.parser.get_function_name_from_selector(
+                    variant_name
+                )
+                # Variants naming the same function share it
+                grouped_rules.setdefault(function_name, []).extend(variant_rules)
+            return grouped_rules
 
-            if function_start >= 0:
-                function_body = "\n".join(function_lines[function_start:])
-                file_content += f"{function_body}\n\n"
+        # Group by selector
+        for rule in rules:
+            func_name = self.parser.get_function_name_from_selector(
+                rule.selector, rule.pseudo_selector
+            )
+            grouped_rules.setdefault(func_name, []).append(rule)
+        return grouped_rules
+
+    def _write_single_rust_file(
+        self,
+        output_path: str,
+        functions: Dict[str, str],
+        modules: Optional[Dict[str, str]] = None,
+    ):
+        """Write all functions to a single Rust file.
+
+        Modules such as ``global`` need their own imports, so they are written
+        as inline modules at the end of the file.
+        """
+        # Functions share one header, importing what each of them uses
+        imports, bodies = [], []
+        for function_code in functions.values():
+            function_imports, function_body = split_rust_items(function_code)
+            imports += function_imports
+            if function_body:
+                bodies.append(function_body)
+
+        file_content = "//! Generated CSS styles\n\n"
+        for statement in merge_use_statements(imports):
+            file_content += f"{statement}\n"
+        file_content += "\n"
+        for function_body in bodies:
+            file_content += f"{function_body.rstrip()}\n\n"
+
+        for module_name, module_code in (modules or {}).items():
+            module_body = textwrap.indent(module_code.strip(), "    ")
+            file_content += f"pub mod {module_name} {{\n{module_body}\n}}\n\n"
 
         # Write to file
         os.makedirs(os.path.dirname(output_path), exist_ok=True)
@@ -226,38 +527,33 @@ class CssToRustConverter:
             f.write(file_content.rstrip() + "\n")
 
     def convert_string(self, css_content: str, **options) -> Dict[str, str]:
-        """Convert CSS string directly to Rust functions."""
+        """Convert CSS string directly to Rust functions.
+
+        Diagnostics from parsing and generating are left in
+        ``self.parser.diagnostics``.
+        """
         # Parse CSS
         rules = self.parser.parse(css_content)
         keyframes = self.parser.keyframes
+        self._configure_generator(**options)
 
         functions = {}
 
-        # Generate functions from rules
-        if options.get("extract_variants", True):
-            variants = self.parser.extract_variants(rules)
-            for variant_name, variant_rules in variants.items():
-                function_name = self.parser.get_function_name_from_selector(
-                    variant_name
-                )
-                functions[function_name] = self.generator.generate_style_function(
-                    function_name, variant_rules
-                )
-        else:
-            # Group by selector
-            grouped_rules = {}
-            for rule in rules:
-                func_name = self.parser.get_function_name_from_selector(
-                    rule.selector, rule.pseudo_selector
-                )
-                if func_name not in grouped_rules:
-                    grouped_rules[func_name] = []
-                grouped_rules[func_name].append(rule)
+        themes, rules = self.parser.split_theme_tokens(rules)
+        if themes:
+            functions["theme"] = self.generator.generate_theme_module(themes)
 
-            for func_name, func_rules in grouped_rules.items():
-                functions[func_name] = self.generator.generate_style_function(
-                    func_name, func_rules
-                )
+        global_rules, rules = self.parser.split_global_rules(rules)
+        font_faces = self.parser.font_faces
+        if global_rules or font_faces or self.parser.layers:
+            functions["global_styles"] = self.generator.generate_global_module(
+                global_rules, font_faces=font_faces, layers=self.parser.layers
+            )
+
+        # Generate functions from rules
+        functions.update(
+            self._generate_style_functions(rules, self.parser.diagnostics, **options)
+        )
 
         # Generate keyframe functions
         if keyframes:
@@ -286,11 +582,21 @@ class CssToRustConverter:
         # Analyze properties
         all_properties = []
         for rule in rules:
-            all_properties.extend(rule.properties.keys())
+            all_properties.extend(d.name for d in rule.declarations)
 
         stats["total_properties"] = len(all_properties)
         stats["unique_properties"] = len(set(all_properties))
 
+        # Cascade layers, in declaration order, with unlayered rules last
+        layers = {name: 0 for name in self.parser.layers}
+        for rule in rules:
+            if rule.layer is not None:
+                name = rule.layer or "(anonymous)"
+                layers[name] = layers.get(name, 0) + 1
+        if layers:
+            layers["(unlayered)"] = sum(1 for r in rules if r.layer is None)
+        stats["layers"] = layers
+
         # Component analysis
         components = self.parser.group_rules_by_component(rules)
         stats["components"] = {
@@ -300,7 +606,7 @@ class CssToRustConverter:
         # Value analysis
         all_values = []
         for rule in rules:
-            all_values.extend(rule.properties.values())
+            all_values.extend(d.value for d in rule.declarations)
 
         # Check how many values can be mapped
         mappable_values = 0
@@ -330,6 +636,39 @@ class CssToRustConverter:
                 "default": True,
                 "type": "boolean",
             },
+            "variant_enums": {
+                "description": "Generate variant enums and one function per component",
+                "default": False,
+                "type": "boolean",
+            },
+            "style_macro": {
+                "description": "Build styles with the stylist style! or css! macro",
+                "default": None,
+                "type": "choice",
+                "choices": ["style", "css"],
+            },
+            "emit_components": {
+                "description": "Generate a Yew function component per style function",
+                "default": False,
+                "type": "boolean",
+            },
+            "target": {
+                "description": "Framework to generate styles for",
+                "default": "yew",
+                "type": "choice",
+                "choices": list(TARGETS),
+            },
+            "style_errors": {
+                "description": "Panic on invalid CSS, return a Result, or offer both",
+                "default": "panic",
+                "type": "choice",
+                "choices": ["panic", "result", "both"],
+            },
+            "cache_styles": {
+                "description": "Build each style once per thread and reuse it",
+                "default": False,
+                "type": "boolean",
+            },
             "include_utilities": {
                 "description": "Include common utility functions",
                 "default": False,
@@ -340,40 +679,89 @@ class CssToRustConverter:
                 "default": True,
                 "type": "boolean",
             },
+            "scss_variables": {
+                "description": "Use SCSS $variables as value mapping candidates",
+                "default": False,
+                "type": "boolean",
+            },
+            "copy_fonts": {
+                "description": "Copy @font-face files next to the output",
+                "default": False,
+                "type": "boolean",
+            },
+            "templates": {
+                "description": "Directory of Jinja2 templates overriding the "
+                "packaged ones",
+                "default": None,
+                "type": "path",
+            },
+            "css_modules": {
+                "description": "Write a stylesheet with hashed class names and "
+                "a module of class name constants instead of style functions",
+                "default": False,
+                "type": "boolean",
+            },
         }
 
-    def validate_css(self, css_content: str) -> List[str]:
+    def validate_css(
+        self, css_content: str, filename: Optional[str] = None
+    ) -> List[Diagnostic]:
         """Validate CSS content and return any warnings or errors."""
-        warnings = []
+        parser = self._get_parser(filename) if filename else self.parser
 
         try:
-            rules = self.parser.parse(css_content)
+            rules = parser.parse(css_content, filename)
         except Exception as e:
-            warnings.append(f"CSS parsing error: {e}")
-            return warnings
+            return [Diagnostic("error", "parse-error", f"CSS parsing error: {e}")]
+
+        diagnostics = list(parser.diagnostics)
 
         # Check for common issues
         for rule in rules:
             # Check for empty rules
-            if not rule.properties:
-                warnings.append(f"Empty rule found: {rule.selector}")
+            if not rule.declarations:
+                diagnostics.append(
+                    Diagnostic(
+                        "warning",
+                        "empty-rule",
+                        f"Empty rule found: {rule.selector}",
+                        rule.span,
+                    )
+                )
 
-            # Check for potentially problematic selectors
-            if " " in rule.selector and not rule.selector.startswith("."):
-                warnings.append(
-                    f"Complex selector may not convert well: {rule.selector}"
+            # Check for selectors the parser could not break down
+            if rule.parsed_selector is None:
+                diagnostics.append(
+                    Diagnostic(
+                        "warning",
+                        "complex-selector",
+                        f"Complex selector may not convert well: {rule.selector}",
+                        rule.span,
+                    )
                 )
 
             # Check for unsupported CSS features
-            for prop_name, prop_value in rule.properties.items():
+            for declaration in rule.declarations:
+                prop_name, prop_value = declaration.name, declaration.value
+                span = declaration.span or rule.span
                 if "calc(" in prop_value:
-                    warnings.append(
-                        f"CSS calc() function found in {rule.selector}.{prop_name}"
+                    diagnostics.append(
+                        Diagnostic(
+                            "warning",
+                            "calc-function",
+                            f"CSS calc() function found in {rule.selector}.{prop_name}",
+                            span,
+                        )
                     )
 
                 if "var(" in prop_value and not prop_value.startswith("var(--"):
-                    warnings.append(
-                        f"Non-standard CSS variable in {rule.selector}.{prop_name}"
+                    diagnostics.append(
+                        Diagnostic(
+                            "warning",
+                            "nonstandard-variable",
+                            f"Non-standard CSS variable in {rule.selector}.{prop_name}",
+                            span,
+                        )
                     )
 
-        return warnings
+        return diagnostics
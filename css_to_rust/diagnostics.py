@@ -0,0 +1,89 @@
+"""Source locations and diagnostics for CSS to Rust conversion."""
+
+from bisect import bisect_right
+from dataclasses import dataclass
+from typing import List, Optional
+
+
+@dataclass
+class SourceSpan:
+    """A 1-based line/column range in a source stylesheet."""
+
+    line: int
+    column: int
+    end_line: Optional[int] = None
+    end_column: Optional[int] = None
+    file: Optional[str] = None
+
+    def __str__(self) -> str:
+        """Format as ``file:line:column``."""
+        return f"{self.file or '<input>'}:{self.line}:{self.column}"
+
+
+@dataclass
+class Diagnostic:
+    """A warning or error about the input CSS."""
+
+    severity: str
+    code: str
+    message: str
+    span: Optional[SourceSpan] = None
+
+    def __str__(self) -> str:
+        """Format as a one-line message."""
+        location = f" at {self.span}" if self.span else ""
+        return f"{self.severity}[{self.code}]: {self.message}{location}"
+
+    def render(self, source: Optional[str] = None) -> str:
+        """Render rustc-style, with a snippet of the offending source."""
+        header = f"{self.severity}[{self.code}]: {self.message}"
+        if not self.span:
+            return header
+
+        gutter = " " * len(str(self.span.line))
+        lines = [header, f"{gutter}--> {self.span}"]
+
+        source_lines = source.splitlines() if source is not None else []
+        if 0 < self.span.line <= len(source_lines):
+            text = source_lines[self.span.line - 1].expandtabs(4)
+            start = self.span.column - 1
+            if self.span.end_line == self.span.line and self.span.end_column:
+                end = self.span.end_column - 1
+            else:
+                end = len(text.rstrip())
+            marker = " " * start + "^" * max(1, end - start)
+
+            lines.append(f"{gutter} |")
+            lines.append(f"{self.span.line} | {text}")
+            lines.append(f"{gutter} | {marker}")
+
+        return "\n".join(lines)
+
+
+class SourceIndex:
+    """Maps character offsets in a source string to line/column spans."""
+
+    def __init__(self, source: str, file: Optional[str] = None):
+        """Index line starts of the source."""
+        self.file = file
+        self.line_starts: List[int] = [0]
+        for index, char in enumerate(source):
+            if char == "\n":
+                self.line_starts.append(index + 1)
+
+    def position(self, offset: int):
+        """Get the 1-based (line, column) of an offset."""
+        line = bisect_right(self.line_starts, offset)
+        return line, offset - self.line_starts[line - 1] + 1
+
+    def span(self, start: int, end: Optional[int] = None) -> SourceSpan:
+        """Create a span from start (inclusive) to end (exclusive) offsets."""
+        line, column = self.position(start)
+        end_line, end_column = self.position(end) if end is not None else (None, None)
+        return SourceSpan(
+            line=line,
+            column=column,
+            end_line=end_line,
+            end_column=end_column,
+            file=self.file,
+        )
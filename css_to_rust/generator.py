@@ -1,115 +1,810 @@
 """Rust code generation utilities for CSS to Rust conversion."""
 
 import os
-from typing import Dict, List, Optional
+import re
+import textwrap
+from dataclasses import replace
+from typing import Dict, List, Optional, Sequence, Tuple
 
 from jinja2 import Environment, FileSystemLoader
 
+from .diagnostics import SourceSpan
 from .mappings import ValueMappings
-from .parser import CssKeyframe, CssRule
+from .parser import (
+    GROUPING_AT_RULES,
+    CssDeclaration,
+    CssFontFace,
+    CssKeyframe,
+    CssParam,
+    CssRule,
+    CssVariantSet,
+)
+from .targets import get_target
+from .utils import (
+    merge_use_statements,
+    module_class_name,
+    sanitize_rust_identifier,
+    scoped_class_name,
+    split_rust_items,
+)
+
+# stylist macros a style function can be built with, and what they return
+STYLE_MACROS = {"style": "Style", "css": "StyleSource"}
+
+# How style functions report invalid CSS: panicking, returning a Result, or
+# a panicking function alongside a Result-returning ``try_`` one
+STYLE_ERRORS = ("panic", "result", "both")
+
+# Parameters of a Rust numeric type replace a value's number and keep its unit
+_NUMERIC_TYPE_RE = re.compile(r"^(?:[iu](?:8|16|32|64|128|size)|f32|f64)$")
+_DIMENSION_RE = re.compile(r"^-?(?:\d+\.?\d*|\.\d+)([a-zA-Z%]*)$")
+
+# Rust chars stripped from string parameters before they're formatted into CSS
+_UNSAFE_STRING_CHARS = r"'{', '}', ';', '<', '\\', '\n'"
+
+# Marks a parameter in rendered CSS until it becomes a format! placeholder
+_PARAM_MARKER_RE = re.compile(r"\x00(\w+)\x00")
+
+# Templates shipped with the package, which a template directory can override
+TEMPLATE_DIR = os.path.join(os.path.dirname(os.path.abspath(__file__)), "templates")
+
+# Class selectors, skipping quoted strings such as attribute values
+_CLASS_SELECTOR_RE = re.compile(r"(\"[^\"]*\"|'[^']*')|\.(-?[_a-zA-Z][\w-]*)")
 
 
 class RustGenerator:
     """Generates Rust code from parsed CSS rules."""
 
-    def __init__(self, mappings: Optional[ValueMappings] = None):
-        """Initialize the Rust generator."""
+    def __init__(
+        self,
+        mappings: Optional[ValueMappings] = None,
+        style_macro: Optional[str] = None,
+        cache_styles: bool = False,
+        style_errors: str = "panic",
+        target: str = "yew",
+        template_dir: Optional[str] = None,
+    ):
+        """Initialize the Rust generator.
+
+        ``style_macro`` selects a key of ``STYLE_MACROS`` to build style
+        functions with, validating their CSS at compile time instead of
+        parsing it in ``Style::new``. With ``cache_styles``, functions build
+        their style once per thread and hand out clones of it.
+        ``style_errors`` is one of ``STYLE_ERRORS``. These apply to stylist
+        targets; ``target`` names the framework in ``TARGETS``.
+        ``template_dir`` holds templates overriding the packaged ones.
+        """
         self.mappings = mappings or ValueMappings()
-        self.template_env = self._setup_templates()
-
-    def _setup_templates(self) -> Environment:
-        """Setup Jinja2 template environment."""
-        # Get template directory
-        current_dir = os.path.dirname(os.path.abspath(__file__))
-        template_dir = os.path.join(os.path.dirname(current_dir), "templates")
-
-        # Fallback to inline templates if directory doesn't exist
-        if not os.path.exists(template_dir):
-            return Environment()
-
-        return Environment(
-            loader=FileSystemLoader(template_dir), trim_blocks=True, lstrip_blocks=True
+        self.style_macro = style_macro
+        self.cache_styles = cache_styles
+        self.style_errors = style_errors
+        self.target = get_target(target)
+        self.template_env = self._setup_templates(template_dir)
+
+    def _setup_templates(self, template_dir: Optional[str] = None) -> Environment:
+        """Setup Jinja2 template environment.
+
+        Templates in ``template_dir`` take precedence over the packaged ones,
+        which cover every output.
+        """
+        search_path = [TEMPLATE_DIR]
+        if template_dir:
+            if not os.path.isdir(template_dir):
+                raise FileNotFoundError(f"Template directory not found: {template_dir}")
+            search_path.insert(0, template_dir)
+
+        env = Environment(
+            loader=FileSystemLoader(search_path), trim_blocks=True, lstrip_blocks=True
         )
+        env.filters["rust_string"] = _rust_string
+        env.filters["raw_string"] = _raw_string
+        return env
+
+    def use_templates(self, template_dir: Optional[str] = None):
+        """Render with the templates in ``template_dir`` ahead of the packaged ones."""
+        self.template_env = self._setup_templates(template_dir)
 
     def generate_style_function(self, function_name: str, rules: List[CssRule]) -> str:
-        """Generate a single Rust style function from CSS rules."""
-        css_content = self._combine_rules_to_css(rules)
+        """Generate a single Rust style function from CSS rules.
+
+        Declarations annotated with ``/* @param name: type */``, or whose
+        property is listed under ``parameters`` in the config, take their
+        value from a parameter of the function.
+        """
+        params, bound_rules = self._bind_params(rules)
+        css_content = self._combine_rules_to_css(bound_rules)
 
         # Apply value mappings
         mapped_css = self._apply_mappings(css_content)
 
-        # Generate function using template or inline
-        try:
-            template = self.template_env.get_template("style_function.rs.j2")
-            return template.render(
-                function_name=function_name,
-                css_content=mapped_css,
-                doc_comment=f"{function_name.replace('_', ' ').title()} styles",
+        return self._render_style_function(
+            function_name, mapped_css, params, rules=rules
+        )
+
+    def _bind_params(
+        self, rules: List[CssRule]
+    ) -> Tuple[Dict[str, str], List[CssRule]]:
+        """Replace parameterised values with markers, collecting the parameters."""
+        params: Dict[str, str] = {}
+        bound_rules = []
+        for rule in rules:
+            declarations = []
+            for declaration in rule.declarations:
+                param = declaration.param
+                if param is None and declaration.name in self.mappings.parameters:
+                    param = CssParam(
+                        sanitize_rust_identifier(declaration.name),
+                        self.mappings.parameters[declaration.name],
+                    )
+                if param is not None:
+                    params.setdefault(param.name, param.rust_type)
+                    value = self._param_value(declaration.value, param)
+                    declaration = replace(declaration, value=value)
+                declarations.append(declaration)
+            bound_rules.append(replace(rule, declarations=declarations))
+        return params, bound_rules
+
+    def _param_value(self, value: str, param: CssParam) -> str:
+        """Get the marked value a parameter supplies, keeping a numeric unit."""
+        match = _DIMENSION_RE.match(value)
+        unit = match.group(1) if match else ""
+        if not (match and _NUMERIC_TYPE_RE.match(param.rust_type)):
+            unit = ""
+        return f"\x00{param.name}\x00{unit}"
+
+    def _render_style_function(
+        self,
+        function_name: str,
+        css_content: str,
+        params: Optional[Dict[str, str]] = None,
+        scoped: bool = True,
+        rules: Sequence[CssRule] = (),
+        rustdoc: str = "",
+    ) -> str:
+        """Render a style function, built at runtime or by a stylist macro.
+
+        Parameterised functions format their CSS at runtime, so they use
+        ``Style::new`` whichever macro was selected. Targets without stylist
+        get a scoped style component instead. ``rules`` are the rules the CSS
+        was rendered from, which the function's ``rustdoc`` describes unless
+        one is given.
+        """
+        rustdoc = rustdoc or self._rules_rustdoc(rules)
+        if not self.target.uses_stylist:
+            return self._render_scoped_style(
+                function_name, css_content, params or {}, scoped, rustdoc
             )
-        except Exception:
-            return self._generate_inline_function(function_name, mapped_css)
 
-    def _generate_inline_function(self, function_name: str, css_content: str) -> str:
-        """Generate Rust function using inline template."""
-        f"{function_name.replace('_', ' ').title()} styles"
+        use_macro = bool(self.style_macro) and not params
+        imports = self._style_imports(use_macro, bool(params))
+        if params:
+            css_content = _format_string(css_content)
+        if use_macro:
+            template_name = "style_macro.rs.j2"
+            items = self._macro_items(function_name, css_content, rustdoc)
+        else:
+            template_name = "style_function.rs.j2"
+            items = self._function_items(function_name, css_content, params, rustdoc)
+
+        template = self.template_env.get_template(template_name)
+        return template.render(
+            function_name=function_name,
+            css_content=css_content,
+            doc_comment=f"{function_name.replace('_', ' ').title()} styles",
+            style_macro=self.style_macro,
+            return_type=STYLE_MACROS.get(self.style_macro, "Style"),
+            cache_styles=self.cache_styles,
+            style_errors=self.style_errors,
+            params=params or {},
+            rules=list(rules),
+            selectors=[rule.full_selector for rule in rules],
+            spans=[rule.span for rule in rules if rule.span],
+            rustdoc=rustdoc,
+            imports=imports,
+            items=items,
+        )
+
+    def _style_imports(self, use_macro: bool, parameterised: bool) -> List[str]:
+        """Get the ``use`` statements of a style function's module."""
+        return_type = STYLE_MACROS[self.style_macro] if use_macro else "Style"
+        if use_macro:
+            imports = [f"use stylist::{{{self.style_macro}, {return_type}}};"]
+        else:
+            imports = ["use stylist::Style;"]
+        # Cached results keep their style in a RefCell, built on success
+        caches_result = self.cache_styles and self.style_errors != "panic"
+        if caches_result and return_type == "Style" and not parameterised:
+            imports.insert(0, "use std::cell::RefCell;")
+        return imports
+
+    def _render_scoped_style(
+        self,
+        function_name: str,
+        css_content: str,
+        params: Dict[str, str],
+        scoped: bool,
+        rustdoc: str = "",
+    ) -> str:
+        """Render CSS scoped to a generated class, with a component mounting it.
+
+        The class name constant is named after the function (``BUTTON``), the
+        component after the function too (``ButtonStyle``). ``scoped=False``
+        leaves CSS such as ``@keyframes`` at the top level.
+        """
+        const_name = function_name.upper()
+        id_expression = _rust_string(function_name.replace("_", "-"))
+        class_name = scoped_class_name(function_name, css_content)
+        css_body = css_content.strip("\n").rstrip()
+        if scoped:
+            id_expression = const_name
+            css_body = f"""        .{class_name} {{
+{textwrap.indent(css_body, "    ")}
+        }}"""
+
+        # Props are owned, as components need 'static props
+        props = ", ".join(
+            f"{name}: {'String' if rust_type == '&str' else rust_type}"
+            for name, rust_type in params.items()
+        )
+
+        format_args = _string_param_args(params)
+        if not scoped:
+            class_name = None
+
+        template = self.template_env.get_template(f"{self.target.name}_style.rs.j2")
+        return template.render(
+            function_name=function_name,
+            doc_comment=f"{function_name.replace('_', ' ').title()} styles",
+            component_name=f"{_pascal_case(function_name)}Style",
+            const_name=const_name,
+            class_name=class_name,
+            css_content=_format_string(css_body) if params else css_body,
+            params=params,
+            props=props,
+            id_expression=id_expression,
+            format_args=format_args,
+            rustdoc=rustdoc,
+            imports=self.target.imports,
+        )
+
+    def generate_variant_function(self, variant_set: CssVariantSet) -> str:
+        """Generate a style function taking one enum per variant axis.
+
+        ``button(variant: ButtonVariant, size: ButtonSize)`` composes the
+        base CSS with the CSS of the chosen variants. Each enum starts with
+        a ``Default`` member adding nothing, unless the CSS defines one.
+        """
+        function_name = variant_set.name
+        axes = []
+        for axis, variants in variant_set.axes.items():
+            arms = {
+                self._variant_member(variant): self._variant_css(rules)
+                for variant, rules in variants.items()
+            }
+            arms = {"Default": '""', **arms}
+            axes.append((axis, self._variant_enum_name(function_name, axis), arms))
+
+        base_css = self._variant_css(variant_set.base)
+        rules = list(variant_set.base)
+        for variants in variant_set.axes.values():
+            for variant_rules in variants.values():
+                rules += variant_rules
+        rustdoc = self._rules_rustdoc(rules)
+
+        items = self._variant_items(function_name, base_css, axes, rustdoc)
+        template = self.template_env.get_template("variant_function.rs.j2")
+        return template.render(
+            function_name=function_name,
+            doc_comment=f"{function_name.replace('_', ' ').title()} styles",
+            base_css=base_css,
+            axes=axes,
+            cache_styles=self.cache_styles,
+            style_errors=self.style_errors,
+            rustdoc=rustdoc,
+            imports=self._variant_imports(),
+            items=items,
+        )
+
+    def _variant_enum_name(self, function_name: str, axis: str) -> str:
+        """Name the enum of a variant axis, such as ``ButtonSize``."""
+        return f"{_pascal_case(function_name)}{axis.title()}"
+
+    def generate_component(
+        self,
+        function_name: str,
+        rules: Sequence[CssRule] = (),
+        variant_set: Optional[CssVariantSet] = None,
+    ) -> str:
+        """Generate a Yew function component applying a style function.
+
+        Its props select the variant set's variants or supply the style's
+        parameters, and pass ``class``, ``onclick`` and ``children`` on to
+        the element.
+        """
+        props: List[Tuple[str, str, bool]] = []
+        args = []
+        if variant_set is not None:
+            for axis in variant_set.axes:
+                enum_name = self._variant_enum_name(function_name, axis)
+                props.append((axis, enum_name, True))
+                args.append(f"props.{axis}")
+        params, _ = self._bind_params(list(rules))
+        for name, rust_type in params.items():
+            props.append((name, "String" if rust_type == "&str" else rust_type, False))
+            args.append(_prop_argument(name, rust_type))
+
+        style = f"{function_name}({', '.join(args)})"
+        # Only variant, parameterised and Style::new functions can fail
+        fallible = variant_set is not None or bool(params) or self.style_macro != "css"
+        if fallible and self.style_errors == "result":
+            style += ".ok()"
+
+        element = "button" if re.search(r"button|btn", function_name) else "div"
+        template = self.template_env.get_template("yew_component.rs.j2")
+        return template.render(
+            function_name=function_name,
+            component_name=_pascal_case(function_name),
+            props=props,
+            style=style,
+            element=element,
+        )
+
+    def _variant_member(self, variant: str) -> str:
+        """Turn a variant name such as ``sm`` into an enum member name."""
+        return sanitize_rust_identifier(variant).title().replace("_", "")
+
+    def _variant_css(self, rules: List[CssRule]) -> str:
+        """Render rules as a raw string literal of mapped CSS."""
+        css_content = self._apply_mappings(self._combine_rules_to_css(rules))
+        if not css_content.strip():
+            return '""'
+        return _raw_string(f"\n{css_content.rstrip()}\n    ")
+
+    def _variant_imports(self) -> List[str]:
+        """Get the ``use`` statements of a variant function's module."""
+        imports = ["use stylist::Style;"]
+        if self.cache_styles:
+            imports[:0] = ["use std::cell::RefCell;", "use std::collections::HashMap;"]
+        return imports
+
+    def _variant_items(
+        self,
+        function_name: str,
+        base_css: str,
+        axes: List[Tuple[str, str, Dict[str, str]]],
+        rustdoc: str = "",
+    ) -> str:
+        """Render the ``pub fn`` items composing a variant set's styles."""
+        body = [f"    let base_css = {base_css};"]
+        for axis, enum_name, arms in axes:
+            body.append(f"    let {axis}_css = match {axis} {{")
+            body += [
+                f"        {enum_name}::{member} => {css},"
+                for member, css in arms.items()
+            ]
+            body.append("    };")
+
+        parts = ", ".join(["base_css"] + [f"{axis}_css" for axis, _, _ in axes])
+        body.append(f"    Style::new([{parts}].concat())")
+        params = [f"{axis}: {enum_name}" for axis, enum_name, _ in axes]
+
+        return self._style_items(
+            function_name,
+            "Style",
+            "\n".join(body),
+            params,
+            chain_indent=8,
+            rustdoc=rustdoc,
+        )
+
+    def _style_items(
+        self,
+        function_name: str,
+        style_type: str,
+        body: str,
+        params: Sequence[str] = (),
+        fallible: bool = True,
+        chain_indent: int = 4,
+        cacheable: bool = True,
+        rustdoc: str = "",
+    ) -> str:
+        """Render the ``pub fn`` items returning the style ``body`` builds.
+
+        A ``fallible`` body evaluates to ``Result<style_type, stylist::Error>``.
+        ``style_errors`` decides whether that result is unwrapped, returned,
+        or returned from a ``try_`` function that a panicking one wraps.
+        Each item is documented with ``rustdoc``.
+        """
+        signature = ", ".join(params)
+        expect = f'.expect("Failed to create {function_name} styles")'
+        if not fallible:
+            return rustdoc + self._style_function(
+                function_name, signature, style_type, body, cacheable
+            )
 
-        return """//! {doc_comment}
+        result_type = f"Result<{style_type}, stylist::Error>"
+        if self.style_errors == "result":
+            return rustdoc + self._style_function(
+                function_name, signature, result_type, body, cacheable, style_type
+            )
 
-use stylist::Style;
+        if self.style_errors == "both":
+            args = ", ".join(param.split(":")[0] for param in params)
+            try_function = self._style_function(
+                f"try_{function_name}",
+                signature,
+                result_type,
+                body,
+                cacheable,
+                style_type,
+            )
+            return f"""{rustdoc}{try_function}
 
-pub fn {function_name}() -> Style {{
-    Style::new(
-        r#"{css_content.strip()}
-    "#,
-    )
-    .expect("Failed to create {function_name} styles")
+{rustdoc}pub fn {function_name}({signature}) -> {style_type} {{
+    try_{function_name}({args}){expect}
 }}"""
 
-    def _combine_rules_to_css(self, rules: List[CssRule]) -> str:
-        """Combine CSS rules back into CSS string."""
-        css_parts = []
+        body += "\n" + " " * chain_indent + expect
+        return rustdoc + self._style_function(
+            function_name, signature, style_type, body, cacheable
+        )
 
-        # Group rules by media query
-        regular_rules = []
-        media_rules = {}
+    def _style_function(
+        self,
+        function_name: str,
+        signature: str,
+        return_type: str,
+        body: str,
+        cacheable: bool = True,
+        result_of: str = "",
+    ) -> str:
+        """Render one style function, caching its value if requested.
+
+        A function returning a ``Result`` of the style ``result_of`` caches
+        only the style, since ``stylist::Error`` can't be cloned.
+        """
+        cache = self.cache_styles and cacheable
+        if cache and result_of:
+            body = self._cached_result_body(result_of, body, signature)
+        elif cache and signature:
+            body = self._cached_map_body(return_type, body, signature)
+        elif cache:
+            body = self._cached_body(return_type, body)
+        return f"pub fn {function_name}({signature}) -> {return_type} {{\n{body}\n}}"
+
+    def _cached_result_body(self, style_type: str, body: str, signature: str) -> str:
+        """Wrap a body building a ``Result`` in a per-thread cache of its style.
+
+        Failures aren't cached, so each call builds the style again and
+        returns its error.
+        """
+        if signature:
+            key_type, key = _cache_key(signature)
+            cache_type = f"HashMap<{key_type}, {style_type}>"
+            cached = f"styles.borrow().get(&{key})"
+            store = f"styles.borrow_mut().insert({key}, style.clone())"
+        else:
+            cache_type = f"Option<{style_type}>"
+            cached = "styles.borrow().as_ref()"
+            store = "*styles.borrow_mut() = Some(style.clone())"
+
+        return f"""    thread_local! {{
+        static STYLES: RefCell<{cache_type}> = RefCell::default();
+    }}
+    STYLES.with(|styles| {{
+        if let Some(style) = {cached} {{
+            return Ok(style.clone());
+        }}
+        let style = {{
+{textwrap.indent(body, "        ")}
+        }}?;
+        {store};
+        Ok(style)
+    }})"""
+
+    def _cached_map_body(self, return_type: str, body: str, signature: str) -> str:
+        """Wrap a function body in a per-thread map of values by argument."""
+        key_type, key = _cache_key(signature)
+        return f"""    thread_local! {{
+        static STYLES: RefCell<HashMap<{key_type}, {return_type}>> = RefCell::default();
+    }}
+    STYLES.with(|styles| {{
+        styles
+            .borrow_mut()
+            .entry({key})
+            .or_insert_with(|| {{
+{textwrap.indent(body, "            ")}
+            }})
+            .clone()
+    }})"""
+
+    def _cached_body(self, return_type: str, expression: str) -> str:
+        """Return a function body evaluating ``expression`` once per thread.
+
+        Stylist styles are reference counted rather than ``Sync``, so each
+        thread keeps its own.
+        """
+        lines = expression.split("\n")
+        lines[0] = f"    static STYLE: {return_type} = {lines[0].strip()}"
+        static = textwrap.indent("\n".join(lines), "    ")
+        return f"""    thread_local! {{
+{static};
+    }}
+    STYLE.with({return_type.split("<")[0]}::clone)"""
+
+    def _function_items(
+        self,
+        function_name: str,
+        css_content: str,
+        params: Optional[Dict[str, str]] = None,
+        rustdoc: str = "",
+    ) -> str:
+        """Render the items of a style function built with ``Style::new``.
+
+        With ``params`` the CSS is a ``format!`` string naming them.
+        """
+        css_body = css_content.strip("\n").rstrip()
+        css_literal = _raw_string(f"\n{css_body}\n    ")
+        expression = f"""    Style::new(
+        {css_literal},
+    )"""
+        if params:
+            expression = f"""    Style::new(format!(
+        {css_literal}{_string_param_args(params)}
+    ))"""
+        return self._style_items(
+            function_name,
+            "Style",
+            expression,
+            [f"{name}: {rust_type}" for name, rust_type in (params or {}).items()],
+            # A style per distinct argument would grow without bound
+            cacheable=not params,
+            rustdoc=rustdoc,
+        )
 
+    def _macro_items(
+        self, function_name: str, css_content: str, rustdoc: str = ""
+    ) -> str:
+        """Render the items of a style function invoking a stylist macro.
+
+        ``$`` starts an interpolation inside the macros, so literal ones are
+        doubled.
+        """
+        css_body = css_content.strip("\n").rstrip().replace("$", "$$")
+        css_literal = _raw_string(f"\n{css_body}\n    ")
+        return_type = STYLE_MACROS[self.style_macro]
+        return self._style_items(
+            function_name,
+            return_type,
+            f"""    {self.style_macro}!(
+        {css_literal}
+    )""",
+            # css! registers its style lazily, so it has nothing to fail
+            fallible=return_type == "Style",
+            rustdoc=rustdoc,
+        )
+
+    def _rules_rustdoc(self, rules: Sequence[CssRule]) -> str:
+        """Render the ``///`` comment tracing a function back to its rules."""
+        return self._rustdoc(
+            [rule.full_selector for rule in rules],
+            [rule.span for rule in rules],
+            [self._source_css(rule) for rule in rules],
+        )
+
+    def _rustdoc(
+        self,
+        selectors: Sequence[str],
+        spans: Sequence[Optional[SourceSpan]],
+        source_css: Sequence[str],
+    ) -> str:
+        """Render a ``///`` comment listing selectors, their source and CSS.
+
+        The CSS is collapsed to a line per rule; rules expanded from one
+        selector list share theirs.
+        """
+        if not selectors:
+            return ""
+
+        names = ", ".join(f"`{selector}`" for selector in dict.fromkeys(selectors))
+        lines = [f"Styles for {names}."]
+        locations = _source_locations(spans)
+        if locations:
+            lines += ["", f"Source: {locations}"]
+        lines += ["", "```css"]
+        lines += dict.fromkeys(" ".join(css.split()) for css in source_css)
+        lines.append("```")
+        return "".join(f"/// {line}".rstrip() + "\n" for line in lines)
+
+    def _source_css(self, rule: CssRule) -> str:
+        """Get a rule's original CSS, inside the at-rules grouping it."""
+        css = rule.raw_css
+        if not css:
+            declarations = " ".join(
+                self._declaration_to_css(declaration)
+                for declaration in rule.declarations
+            )
+            css = f"{rule.full_selector} {{ {declarations} }}"
+
+        # Innermost first: @container inside @supports inside @media
+        for keyword, field_name in reversed(GROUPING_AT_RULES.items()):
+            condition = getattr(rule, field_name)
+            if condition is not None:
+                prelude = f"{keyword} {condition}".rstrip()
+                css = f"{prelude} {{ {css} }}"
+        return css
+
+    def _combine_rules_to_css(
+        self,
+        rules: List[CssRule],
+        indent: str = "        ",
+        global_scope: bool = False,
+    ) -> str:
+        """Combine CSS rules back into CSS string.
+
+        Component rules are written relative to the component with ``&``;
+        ``global_scope`` writes every rule under its full selector instead.
+        """
+        css_parts = self._render_grouped_rules(rules, indent, 0, global_scope)
+        return "\n\n".join(part for part in css_parts if part)
+
+    def _render_grouped_rules(
+        self,
+        rules: List[CssRule],
+        indent: str,
+        level: int = 0,
+        global_scope: bool = False,
+    ) -> List[str]:
+        """Render rules, wrapping them in blocks for their grouping at-rules.
+
+        Blocks nest in ``GROUPING_AT_RULES`` order, so a rule under both
+        ``@media`` and ``@supports`` ends up in an ``@supports`` block inside
+        the ``@media`` one, and ``@layer`` blocks wrap everything else. Blocks
+        and the rules between them keep their source order, which decides the
+        cascade when more than one applies; consecutive rules under the same
+        at-rule share one.
+        """
+        at_rules = list(GROUPING_AT_RULES.items())
+        css_parts: List[str] = []
+        for block_level, condition, group in self._at_rule_runs(rules, level):
+            if block_level is None:
+                css_parts += self._render_rules(
+                    group, indent, global_scope, bare=not css_parts
+                )
+                continue
+
+            if at_rules[block_level][1] in ("media_query", "container_query"):
+                condition = self.mappings.map_condition(condition)
+            body = "\n".join(
+                part
+                for part in self._render_grouped_rules(
+                    group, indent + "    ", block_level + 1, global_scope
+                )
+                if part
+            )
+            # Anonymous layers have no prelude beyond the keyword
+            prelude = f"{at_rules[block_level][0]} {condition}".rstrip()
+            css_parts.append(f"{indent}{prelude} {{\n{body}\n{indent}}}")
+
+        return css_parts
+
+    def _at_rule_runs(
+        self, rules: List[CssRule], level: int
+    ) -> List[Tuple[Optional[int], Optional[str], List[CssRule]]]:
+        """Split rules into runs sharing the outermost at-rule block they need.
+
+        Each run is ``(level, condition, rules)``, with a ``None`` level for
+        rules needing no further block.
+        """
+        at_rules = list(GROUPING_AT_RULES.items())
+        runs: List[Tuple[Optional[int], Optional[str], List[CssRule]]] = []
         for rule in rules:
-            if rule.media_query:
-                if rule.media_query not in media_rules:
-                    media_rules[rule.media_query] = []
-                media_rules[rule.media_query].append(rule)
+            # Each rule goes into the outermost block it still needs
+            block_level: Optional[int] = None
+            condition = None
+            for index in range(level, len(at_rules)):
+                condition = getattr(rule, at_rules[index][1])
+                if condition is not None:
+                    block_level = index
+                    break
+
+            if runs and runs[-1][:2] == (block_level, condition):
+                runs[-1][2].append(rule)
             else:
-                regular_rules.append(rule)
+                runs.append((block_level, condition, [rule]))
+        return runs
+
+    def _render_rules(
+        self,
+        rules: List[CssRule],
+        indent: str = "        ",
+        global_scope: bool = False,
+        bare: bool = True,
+    ) -> List[str]:
+        """Render rules, sharing one block between members of a selector list.
+
+        Rules expanded from the same selector list with identical declarations
+        collapse into a single ``&, &:hover { ... }`` block when they end up
+        in the same function; across functions each gets its own copy. Rules
+        keep their source order, which decides the cascade between them, so
+        declarations on the component itself are only written bare while
+        ``bare`` and no nested block precedes them; later ones go in an
+        ``& { ... }`` block.
+        """
+        blocks: List[Tuple[List[Optional[str]], CssRule]] = []
+        shared: Dict[tuple, List[Optional[str]]] = {}
 
-        # Add regular rules
-        for rule in regular_rules:
-            css_parts.append(self._rule_to_css_string(rule))
+        for rule in rules:
+            if global_scope:
+                selector: Optional[str] = rule.full_selector
+            else:
+                selector = self._nested_selector(rule)
+                if selector is not None:
+                    bare = False
+                elif not bare:
+                    selector = "&"
+            key = (rule.selector_group, self._declarations_key(rule))
+
+            if rule.selector_group and key in shared:
+                shared[key].append(selector)
+                continue
 
-        # Add media query rules
-        for media_query, media_rule_list in media_rules.items():
-            css_parts.append(f"\n        @media {media_query} {{")
-            for rule in media_rule_list:
-                css_parts.append(self._rule_to_css_string(rule, indent="            "))
-            css_parts.append("        }")
+            selectors = [selector]
+            blocks.append((selectors, rule))
+            if rule.selector_group:
+                shared[key] = selectors
+
+        return [
+            self._rule_to_css_string(rule, indent, selectors)
+            for selectors, rule in blocks
+        ]
+
+    def _declarations_key(self, rule: CssRule) -> tuple:
+        """Get a hashable key for a rule's declarations, order included."""
+        return tuple((d.name, d.value, d.important) for d in rule.declarations)
+
+    def _nested_selector(self, rule: CssRule) -> Optional[str]:
+        """Get the rule's selector relative to its component (None for itself)."""
+        if rule.parsed_selector is not None:
+            selector = rule.parsed_selector.nested_css()
+        elif rule.pseudo_selector:
+            selector = f"&:{rule.pseudo_selector}"
+        else:
+            selector = "&"
 
-        return "\n".join(css_parts)
+        return None if selector == "&" else selector
 
-    def _rule_to_css_string(self, rule: CssRule, indent: str = "        ") -> str:
+    def _rule_to_css_string(
+        self,
+        rule: CssRule,
+        indent: str = "        ",
+        selectors: Optional[List[Optional[str]]] = None,
+    ) -> str:
         """Convert a CSS rule back to CSS string."""
-        properties_str = ""
+        if not rule.declarations:
+            return ""
 
-        for prop_name, prop_value in rule.properties.items():
-            properties_str += f"\n{indent}    {prop_name}: {prop_value};"
+        if selectors is None:
+            selectors = [self._nested_selector(rule)]
 
-        if rule.pseudo_selector:
-            selector = f"&:{rule.pseudo_selector}"
-        else:
-            selector = "&" if not rule.selector.startswith(".") else ""
+        if selectors == [None]:
+            # Declarations on the component itself need no selector block
+            return "\n".join(
+                f"{indent}{self._declaration_to_css(declaration)}"
+                for declaration in rule.declarations
+            )
+
+        properties_str = ""
+        for declaration in rule.declarations:
+            properties_str += f"\n{indent}    {self._declaration_to_css(declaration)}"
 
-        if properties_str:
-            return f"{indent}{selector} {{{properties_str}\n{indent}}}"
+        selector = ", ".join(s or "&" for s in selectors)
+        return f"{indent}{selector} {{{properties_str}\n{indent}}}"
 
-        return ""
+    def _declaration_to_css(self, declaration: CssDeclaration) -> str:
+        """Convert a declaration back to a ``name: value;`` line."""
+        important = " !important" if declaration.important else ""
+        return f"{declaration.name}: {declaration.value}{important};"
 
     def _apply_mappings(self, css_content: str) -> str:
         """Apply value mappings to CSS content."""
@@ -117,8 +812,13 @@ pub fn {function_name}() -> Style {{
         mapped_lines = []
 
         for line in lines:
-            # Skip non-property lines
-            if ":" not in line or line.strip().startswith("@"):
+            # Skip non-property lines, including nested block headers
+            stripped = line.strip()
+            if (
+                ":" not in line
+                or stripped.startswith("@")
+                or stripped.endswith(("{", "}"))
+            ):
                 mapped_lines.append(line)
                 continue
 
@@ -134,11 +834,17 @@ pub fn {function_name}() -> Style {{
             # Extract property name
             prop_name = prop_part.split()[-1] if prop_part.split() else ""
 
+            # Map the bare value, keeping its priority out of the lookup
+            priority = ""
+            if value_part.endswith("!important"):
+                value_part = value_part[: -len("!important")].rstrip()
+                priority = " !important"
+
             # Map the value
             mapped_value = self.mappings.map_value(prop_name, value_part)
 
             # Reconstruct line
-            mapped_line = f"{prop_part}: {mapped_value};"
+            mapped_line = f"{prop_part}: {mapped_value}{priority};"
             mapped_lines.append(mapped_line)
 
         return "\n".join(mapped_lines)
@@ -147,69 +853,199 @@ pub fn {function_name}() -> Style {{
         self, component_name: str, functions: Dict[str, str]
     ) -> str:
         """Generate a complete Rust module for a component."""
-        try:
-            template = self.template_env.get_template("component.rs.j2")
-            return template.render(
-                component_name=component_name,
-                functions=functions,
-                doc_comment=f"{component_name.replace('_', ' ').title()} component styles",
-            )
-        except Exception:
-            return self._generate_inline_component_module(component_name, functions)
-
-    def _generate_inline_component_module(
-        self, component_name: str, functions: Dict[str, str]
+        doc_comment = f"{component_name.replace('_', ' ').title()} component styles"
+        imports, items = self._module_items(functions)
+        template = self.template_env.get_template("component.rs.j2")
+        module = template.render(
+            component_name=component_name,
+            functions=functions,
+            doc_comment=doc_comment,
+            imports=imports,
+            items=items,
+        )
+        return module.rstrip() + "\n"
+
+    def _module_items(self, functions: Dict[str, str]) -> Tuple[List[str], List[str]]:
+        """Split functions into the merged imports and the items of a module.
+
+        Functions share one header, importing what each of them uses.
+        """
+        imports, items = [], []
+        for function_code in functions.values():
+            function_imports, function_body = split_rust_items(function_code)
+            imports += function_imports
+            if function_body:
+                items.append(function_body.rstrip())
+
+        return merge_use_statements(imports), items
+
+    def generate_global_module(
+        self,
+        rules: List[CssRule],
+        font_faces: Sequence[CssFontFace] = (),
+        layers: Sequence[str] = (),
     ) -> str:
-        """Generate component module using inline template."""
-        f"{component_name.replace('_', ' ').title()} component styles"
-
-        module_content = """//! {doc_comment}
-
-use stylist::Style;
+        """Generate a module holding the global styles and a Yew ``<Global>``.
+
+        ``layers`` is the declared cascade layer order, stated ahead of the
+        styles so that it holds whichever style is mounted first.
+        """
+        css_parts = self._global_css_parts(rules, font_faces, layers, "    ")
+        mapped_css = "\n\n".join(part for part in css_parts if part)
+        mapped_css = mapped_css.strip("\n").rstrip()
+
+        template = self.template_env.get_template("global.rs.j2")
+        return template.render(
+            css_content=mapped_css,
+            target=self.target.name,
+            style_errors=self.style_errors,
+            rustdoc=self._global_rustdoc(rules, font_faces),
+        )
 
-"""
+    def _global_rustdoc(
+        self, rules: Sequence[CssRule], font_faces: Sequence[CssFontFace]
+    ) -> str:
+        """Render the ``///`` comment tracing global styles to their rules.
 
-        for function_name, function_code in functions.items():
-            # Extract just the function part (remove the header and import)
-            function_lines = function_code.split("\n")
-            function_start = -1
+        Styles holding nothing but the layer order get a fixed summary.
+        """
+        selectors = ["@font-face"] * len(font_faces)
+        spans = [font_face.span for font_face in font_faces]
+        source_css = [self._font_face_to_css_string(f) for f in font_faces]
+        for rule in rules:
+            selectors.append(rule.full_selector)
+            spans.append(rule.span)
+            source_css.append(self._source_css(rule))
+
+        rustdoc = self._rustdoc(selectors, spans, source_css)
+        return rustdoc or "/// Global styles declaring the cascade layer order.\n"
+
+    def _global_css_parts(
+        self,
+        rules: List[CssRule],
+        font_faces: Sequence[CssFontFace],
+        layers: Sequence[str],
+        indent: str,
+    ) -> List[str]:
+        """Render the layer order, font faces and mapped rules of global CSS."""
+        css_parts = [_layer_statement(layers, indent)]
+        # Font descriptors such as font-weight can't take theme variables
+        for font_face in font_faces:
+            font_face_css = textwrap.dedent(self._font_face_to_css_string(font_face))
+            css_parts.append(textwrap.indent(font_face_css, indent))
+        css_content = self._combine_rules_to_css(rules, indent, global_scope=True)
+        css_parts.append(self._apply_mappings(css_content))
+        return css_parts
+
+    def _font_face_to_css_string(self, font_face: CssFontFace) -> str:
+        """Convert an ``@font-face`` rule back to CSS string."""
+        declarations = "".join(
+            f"\n        {self._declaration_to_css(declaration)}"
+            for declaration in font_face.declarations
+        )
+        return f"    @font-face {{{declarations}\n    }}"
+
+    def hash_class_names(
+        self, rules: List[CssRule], stylesheet: str
+    ) -> Tuple[List[CssRule], Dict[str, str]]:
+        """Rename the class selectors of rules to names hashed per stylesheet.
+
+        Returns the renamed rules and a map of the original class names to
+        their hashed names, in order of appearance.
+        """
+        classes: Dict[str, str] = {}
+
+        def rename(match: re.Match) -> str:
+            if match.group(1):
+                return match.group(1)
+            name = match.group(2)
+            classes.setdefault(name, module_class_name(name, stylesheet))
+            return f".{classes[name]}"
+
+        renamed = [
+            replace(
+                rule,
+                selector=_CLASS_SELECTOR_RE.sub(rename, rule.full_selector),
+                parsed_selector=None,
+                pseudo_selector=None,
+            )
+            for rule in rules
+        ]
+        return renamed, classes
+
+    def generate_stylesheet(
+        self,
+        rules: List[CssRule],
+        keyframes: Sequence[CssKeyframe] = (),
+        font_faces: Sequence[CssFontFace] = (),
+        layers: Sequence[str] = (),
+    ) -> str:
+        """Generate a plain stylesheet, with values mapped, from parsed CSS.
+
+        ``layers`` is the declared cascade layer order, stated first.
+        """
+        css_parts = self._global_css_parts(rules, font_faces, layers, "")
+        css_parts += [
+            textwrap.dedent(self._apply_mappings(self._keyframe_to_css_string(k)))
+            for k in keyframes
+        ]
+        return "\n\n".join(part for part in css_parts if part) + "\n"
+
+    def generate_css_module(self, classes: Dict[str, str], stylesheet: str) -> str:
+        """Generate a module of class name constants for a CSS module stylesheet.
+
+        ``classes`` maps class names to their hashed names, as returned by
+        ``hash_class_names``. The stylesheet is embedded with ``include_str!``,
+        so it has to sit next to the module.
+        """
+        constants = {
+            sanitize_rust_identifier(name).upper(): hashed_name
+            for name, hashed_name in classes.items()
+        }
 
-            for i, line in enumerate(function_lines):
-                if line.startswith("pub fn "):
-                    function_start = i
-                    break
+        template = self.template_env.get_template("css_module.rs.j2")
+        return template.render(constants=constants, stylesheet=stylesheet)
+
+    def generate_theme_module(self, themes: Dict[str, Dict[str, str]]) -> str:
+        """Generate a ``Theme`` struct and token constants from custom properties.
+
+        ``themes`` maps theme names to their tokens, as returned by
+        ``CssParser.split_theme_tokens``. Themes inherit tokens they don't
+        override from ``default``; tokens no theme defines stay ``initial``.
+        """
+        base = themes.get("default", {})
+        tokens = list(base)
+        for values in themes.values():
+            tokens.extend(name for name in values if name not in tokens)
+
+        fields = {name: self._token_identifier(name) for name in tokens}
+        theme_values = {
+            theme: {
+                name: values.get(name, base.get(name, "initial")) for name in tokens
+            }
+            for theme, values in {"default": base, **themes}.items()
+        }
 
-            if function_start >= 0:
-                function_body = "\n".join(function_lines[function_start:])
-                module_content += f"{function_body}\n\n"
+        template = self.template_env.get_template("theme.rs.j2")
+        return template.render(
+            fields=fields,
+            themes=theme_values,
+            theme_names={
+                theme: sanitize_rust_identifier(theme).upper() for theme in theme_values
+            },
+            imports=self.target.theme_imports,
+            target=self.target.name,
+            style_errors=self.style_errors,
+        )
 
-        return module_content.rstrip() + "\n"
+    def _token_identifier(self, name: str) -> str:
+        """Turn a ``--custom-property`` name into a snake_case Rust identifier."""
+        return sanitize_rust_identifier(name[2:].replace("-", "_")).lower()
 
     def generate_mod_file(self, components: List[str]) -> str:
         """Generate a mod.rs file for the components."""
-        try:
-            template = self.template_env.get_template("mod_file.rs.j2")
-            return template.render(components=components)
-        except Exception:
-            return self._generate_inline_mod_file(components)
-
-    def _generate_inline_mod_file(self, components: List[str]) -> str:
-        """Generate mod.rs file using inline template."""
-        mod_content = """//! Style modules
-
-"""
-
-        # Add module declarations
-        for component in sorted(components):
-            mod_content += f"pub mod {component};\n"
-
-        mod_content += "\n// Re-export all component styles\n"
-
-        # Add re-exports
-        for component in sorted(components):
-            mod_content += f"pub use {component}::*;\n"
-
-        return mod_content
+        template = self.template_env.get_template("mod_file.rs.j2")
+        return template.render(components=sorted(components)).rstrip() + "\n"
 
     def generate_keyframe_functions(
         self, keyframes: List[CssKeyframe]
@@ -221,9 +1057,13 @@ use stylist::Style;
             function_name = f"animation_{keyframe.name.lower()}"
             css_content = self._keyframe_to_css_string(keyframe)
             mapped_css = self._apply_mappings(css_content)
+            rustdoc = self._rustdoc(
+                [f"@keyframes {keyframe.name}"], [keyframe.span], [css_content]
+            )
 
-            functions[function_name] = self._generate_inline_function(
-                function_name, mapped_css
+            # Keyframes can't be nested in a scoping rule
+            functions[function_name] = self._render_style_function(
+                function_name, mapped_css, scoped=False, rustdoc=rustdoc
             )
 
         return functions
@@ -297,28 +1137,40 @@ use stylist::Style;
         }
 
         for util_name, properties in utility_patterns.items():
-            css_content = "\n        ".join(f"{prop};" for prop in properties)
+            css_content = "\n".join(f"        {prop};" for prop in properties)
             mapped_css = self._apply_mappings(css_content)
-            utilities[util_name] = self._generate_inline_function(util_name, mapped_css)
+            rustdoc = f"/// Utility styles for `{'; '.join(properties)}`.\n"
+            utilities[util_name] = self._render_style_function(
+                util_name, mapped_css, rustdoc=rustdoc
+            )
 
         return utilities
 
     def create_file_structure(
-        self, output_dir: str, components: Dict[str, Dict[str, str]]
+        self,
+        output_dir: str,
+        components: Dict[str, Dict[str, str]],
+        modules: Optional[Dict[str, str]] = None,
     ):
-        """Create the complete file structure in the output directory."""
+        """Create the complete file structure in the output directory.
+
+        ``modules`` maps extra module names, such as ``global``, to their
+        already generated source.
+        """
         # Create output directory if it doesn't exist
         os.makedirs(output_dir, exist_ok=True)
 
-        # Generate component files
+        # Generate component modules
+        sources = {
+            component_name: self.generate_component_module(component_name, functions)
+            for component_name, functions in components.items()
+        }
+        sources.update(modules or {})
+
+        # Write module files
         component_names = []
-        for component_name, functions in components.items():
+        for component_name, module_content in sources.items():
             component_names.append(component_name)
-
-            # Generate component module
-            module_content = self.generate_component_module(component_name, functions)
-
-            # Write component file
             component_file = os.path.join(output_dir, f"{component_name}.rs")
             with open(component_file, "w", encoding="utf-8") as f:
                 f.write(module_content)
@@ -330,3 +1182,87 @@ use stylist::Style;
             f.write(mod_content)
 
         return component_names
+
+
+def _source_locations(spans: Sequence[Optional[SourceSpan]]) -> str:
+    """Describe the lines spans cover, such as ``app.css``, lines 3-12."""
+    line_ranges: Dict[Optional[str], List[int]] = {}
+    for span in spans:
+        if span is None:
+            continue
+        end_line = max(span.line, span.end_line or span.line)
+        line_range = line_ranges.setdefault(span.file, [span.line, end_line])
+        line_range[0] = min(line_range[0], span.line)
+        line_range[1] = max(line_range[1], end_line)
+
+    locations = []
+    for file, (first, last) in line_ranges.items():
+        lines = f"line {first}" if first == last else f"lines {first}-{last}"
+        locations.append(f"`{file}`, {lines}" if file else lines)
+    return "; ".join(locations)
+
+
+def _cache_key(signature: str) -> Tuple[str, str]:
+    """Get the type and expression of the cache key for a function's parameters."""
+    params = [param.split(": ") for param in signature.split(", ")]
+    key_type = ", ".join(param_type for _, param_type in params)
+    key = ", ".join(name for name, _ in params)
+    if len(params) > 1:
+        return f"({key_type})", f"({key})"
+    return key_type, key
+
+
+def _layer_statement(layers: Sequence[str], indent: str = "") -> str:
+    """Render the ``@layer`` statement declaring the order of cascade layers."""
+    return f"{indent}@layer {', '.join(layers)};" if layers else ""
+
+
+def _pascal_case(name: str) -> str:
+    """Turn a snake_case name into a PascalCase type name."""
+    return "".join(word.title() for word in name.split("_"))
+
+
+def _prop_argument(name: str, rust_type: str) -> str:
+    """Pass a component prop on to the style function parameter it came from."""
+    if rust_type == "&str":
+        return f"&props.{name}"
+    if rust_type == "bool" or _NUMERIC_TYPE_RE.match(rust_type):
+        return f"props.{name}"
+    return f"props.{name}.clone()"
+
+
+def _string_param_args(params: Dict[str, str]) -> str:
+    """Render the ``format!`` arguments stripping string parameters.
+
+    A string could otherwise end its declaration or rule (``red; } body {``)
+    and add CSS of its own, or close the ``<style>`` element mounting it.
+    """
+    args = [
+        f"\n        {name} = {name}.replace([{_UNSAFE_STRING_CHARS}], \"\"),"
+        for name, rust_type in params.items()
+        if rust_type == "&str"
+    ]
+    return "," + "".join(args) if args else ""
+
+
+def _format_string(css_content: str) -> str:
+    """Escape CSS braces for ``format!`` and turn parameter markers into names."""
+    escaped = css_content.replace("{", "{{").replace("}", "}}")
+    return _PARAM_MARKER_RE.sub(r"{\1}", escaped)
+
+
+def _rust_string(value: str) -> str:
+    """Quote a value as a Rust string literal."""
+    escaped = value.replace("\\", "\\\\").replace('"', '\\"').replace("\n", "\\n")
+    return f'"{escaped}"'
+
+
+def _raw_string(value: str) -> str:
+    """Quote a value as a Rust raw string literal.
+
+    The literal takes one more ``#`` than the longest run following a ``"``
+    in the value, so CSS such as ``a[href^="#"]`` can't close it early.
+    """
+    runs = [len(run) + 1 for run in re.findall(r'"(#*)', value)]
+    hashes = "#" * max(runs + [1])
+    return f'r{hashes}"{value}"{hashes}'
@@ -2,8 +2,16 @@
 
 import json
 import os
+import re
 from typing import Any, Dict, Optional
 
+# A size feature of a media or container condition, in either ``(min-width:
+# 768px)`` or range ``(width >= 768px)`` form
+_SIZE_FEATURE_RE = re.compile(
+    r"\(\s*(?:min-|max-)?(?:width|height|inline-size|block-size)\s*"
+    r"(?::|[<>]?=?)\s*((?:var\([^()]*\)|[^()<>=])+?)\s*\)"
+)
+
 
 class ValueMappings:
     """Handles mapping CSS values to Rust/theme equivalents."""
@@ -11,9 +19,14 @@ class ValueMappings:
     def __init__(self, config_path: Optional[str] = None):
         """Initialize with default or custom mappings."""
         self.mappings = self._load_default_mappings()
+        self.candidates: Dict[str, str] = {}
+        self.use_candidates = False
+        # Properties whose values become style function parameters, by type
+        self.parameters: Dict[str, str] = {}
 
         if config_path and os.path.exists(config_path):
             custom_mappings = self._load_custom_mappings(config_path)
+            self.parameters = custom_mappings.pop("parameters", {})
             self._merge_mappings(custom_mappings)
 
     def _load_default_mappings(self) -> Dict[str, Any]:
@@ -173,15 +186,54 @@ class ValueMappings:
         category = self._get_category_for_property(property_name)
 
         if category and category in self.mappings:
-            return self.mappings[category].get(value, value)
+            if value in self.mappings[category]:
+                return self.mappings[category][value]
+        else:
+            # Check all categories if no specific category found
+            for cat_values in self.mappings.values():
+                if isinstance(cat_values, dict) and value in cat_values:
+                    return cat_values[value]
 
-        # Check all categories if no specific category found
-        for cat_values in self.mappings.values():
-            if isinstance(cat_values, dict) and value in cat_values:
-                return cat_values[value]
+        # Fall back to candidates only when explicitly enabled
+        if self.use_candidates:
+            return self.candidates.get(value, value)
 
         return value
 
+    def map_condition(self, condition: str) -> str:
+        """Map breakpoints in the size features of a query condition.
+
+        Custom properties don't work in media or container conditions, so a
+        breakpoint mapped to ``var(--breakpoint-md)`` is written as its
+        literal length instead, and a ``var()`` naming a breakpoint resolves
+        back to that length. Only mappings to another literal are applied.
+        """
+        breakpoints = self.mappings.get("breakpoints", {})
+        lengths = {
+            reference: length
+            for length, reference in breakpoints.items()
+            if reference.startswith("var(")
+        }
+
+        def replace(match: re.Match) -> str:
+            value = match.group(1)
+            mapped = lengths.get(value, breakpoints.get(value, value))
+            if mapped.startswith("var("):
+                mapped = value
+            start, end = match.start(1) - match.start(), match.end(1) - match.start()
+            return f"{match.group()[:start]}{mapped}{match.group()[end:]}"
+
+        return _SIZE_FEATURE_RE.sub(replace, condition)
+
+    def add_candidates(self, variables: Dict[str, str]):
+        """Offer named variables (e.g. SCSS ``$variables``) as mapping candidates."""
+        for name, value in variables.items():
+            self.candidates.setdefault(value.strip(), f"var(--{name})")
+
+    def get_candidates(self) -> Dict[str, str]:
+        """Get mapping candidates as value -> CSS variable reference."""
+        return self.candidates.copy()
+
     def _get_category_for_property(self, property_name: str) -> Optional[str]:
         """Determine which mapping category to use for a CSS property."""
         property_name = property_name.lower()
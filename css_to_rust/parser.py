@@ -1,21 +1,622 @@
 """CSS parsing utilities for converting CSS to Rust."""
 
 import re
-from dataclasses import dataclass
-from typing import Dict, List, Optional, Tuple
+from dataclasses import dataclass, field, replace
+from pathlib import Path
+from typing import Dict, Iterable, List, Optional, Set, Tuple, Union
 
 import cssutils
 
+from .diagnostics import Diagnostic, SourceIndex, SourceSpan
+from .tokenizer import (
+    AtRule,
+    Declaration,
+    Node,
+    ParseError,
+    parse_stylesheet,
+    serialize,
+)
+from .utils import sanitize_rust_identifier
+
+
+_IDENT_RE = re.compile(r"(?:[\w-]|\\.|[^\x00-\x7f])+")
+
+_COMBINATORS = (">", "+", "~")
+
+_SCSS_VARIABLE_RE = re.compile(
+    r"^\$([\w-]+)\s*:\s*(.*?)\s*(!default|!global)?\s*$", re.DOTALL
+)
+
+_SCSS_UNSUPPORTED = ("@if", "@else", "@each", "@for", "@while", "@function")
+
+_SCSS_CALL_RE = re.compile(r"^\s*([\w-]+)\s*(?:\((.*)\))?\s*$", re.DOTALL)
+
+_IMPORTANT_RE = re.compile(r"\s*!\s*important\s*$", re.IGNORECASE)
+
+# An ``@import`` of one file, with the media query list it applies under
+_IMPORT_RE = re.compile(
+    r"@import\s+(?:url\(\s*)?[\"']?([^\"')\s;]+)[\"']?\s*\)?"
+    r"(?:\s*([^;,\"'\s][^;]*))?"
+)
+
+# Import conditions other than media queries, which rules can't record
+_IMPORT_CONDITION_RE = re.compile(r"^(?:layer|supports)\b")
+
+# A ``[data-theme=dark]`` selector switching to a named theme
+_THEME_SELECTOR_RE = re.compile(
+    r"^(?::root|html)?\[data-theme\s*=\s*([\"']?)([\w-]+)\1\]$"
+)
+
+# Variant name patterns by the axis they vary, tried in this order before the
+# catch-all ``base-anything`` split
+VARIANT_AXES = {
+    "variant": r"primary|secondary|success|danger|warning|info|light|dark",
+    "size": r"small|sm|large|lg|xl|xs",
+    "style": r"outline|solid|ghost|link",
+}
+
+# Imports with a scheme (``https:``, ``data:``) or protocol-relative URLs
+REMOTE_URL_RE = re.compile(r"^(?:[a-zA-Z][\w+.-]*:|//)")
+
+# A ``/* @param name: type */`` comment making the declaration it follows, on
+# the same line, a parameter of the generated style function
+_PARAM_COMMENT_RE = re.compile(r"/\*\s*@param\s+(\w+)\s*:\s*(.+?)\s*\*/")
+
+# Grouping at-rules and the CssRule field holding their prelude, from the
+# outermost to the innermost wrapper the generator emits
+GROUPING_AT_RULES = {
+    "@layer": "layer",
+    "@media": "media_query",
+    "@supports": "supports_condition",
+    "@container": "container_query",
+}
+
+# At-rules cssutils drops, so stylesheets using them take the built-in parser
+_CSSUTILS_UNSUPPORTED = ("@supports", "@container", "@layer")
+
+_AT_KEYWORD_RE = re.compile(r"@[\w-]+")
+
+# A media query with a media type, ``[not|only] screen [and <condition>]``
+_MEDIA_TYPE_QUERY_RE = re.compile(
+    r"^(?:(not|only)\s+)?(?!(?:not|only|and|or)\b)([a-zA-Z][\w-]*)"
+    r"(?:\s+and\s+(.+))?$",
+    re.IGNORECASE | re.DOTALL,
+)
+
+# A container query naming its container, e.g. ``card (min-width: 400px)``
+_CONTAINER_NAME_RE = re.compile(r"^(?!(?:not|and|or)\b)[a-zA-Z_-][\w-]*\s")
+
+
+@dataclass
+class SimpleSelector:
+    """A single simple selector, e.g. ``.title``, ``[disabled]`` or ``:hover``."""
+
+    kind: str
+    name: str
+    argument: Optional[str] = None
+
+    def to_css(self) -> str:
+        """Serialize the simple selector back to CSS."""
+        argument = f"({self.argument})" if self.argument is not None else ""
+
+        if self.kind == "class":
+            return f".{self.name}"
+        elif self.kind == "id":
+            return f"#{self.name}"
+        elif self.kind == "attribute":
+            return f"[{self.name}]"
+        elif self.kind == "pseudo_class":
+            return f":{self.name}{argument}"
+        elif self.kind == "pseudo_element":
+            return f"::{self.name}{argument}"
+
+        # type, universal and nesting selectors serialize as their name
+        return self.name
+
+    @property
+    def is_pseudo(self) -> bool:
+        """Whether this is a pseudo-class or pseudo-element."""
+        return self.kind in ("pseudo_class", "pseudo_element")
+
+
+@dataclass
+class CompoundSelector:
+    """A sequence of simple selectors not separated by combinators."""
+
+    parts: List[SimpleSelector]
+    combinator: Optional[str] = None
+
+    def to_css(self) -> str:
+        """Serialize the compound selector back to CSS."""
+        return "".join(part.to_css() for part in self.parts)
+
+    def owner_index(self) -> Optional[int]:
+        """Index of the simple selector that names the styled element."""
+        for kinds in (("class",), ("id",), ("type", "universal", "nesting")):
+            for index, part in enumerate(self.parts):
+                if part.kind in kinds:
+                    return index
+        return None
+
+
+@dataclass
+class Selector:
+    """A complex selector: compound selectors joined by combinators.
+
+    The combinator of each compound (``" "``, ``">"``, ``"+"`` or ``"~"``)
+    joins it to the compound before it; the first compound has none.
+    """
+
+    compounds: List[CompoundSelector]
+
+    def to_css(self) -> str:
+        """Serialize the selector back to CSS."""
+        return self._join(compound.to_css() for compound in self.compounds)
+
+    @property
+    def owner(self) -> str:
+        """The simple selector the rule belongs to, e.g. ``.card``."""
+        first = self.compounds[0]
+        index = first.owner_index()
+        if index is None:
+            return first.to_css()
+        return first.parts[index].to_css()
+
+    @property
+    def is_global(self) -> bool:
+        """Whether the selector names no class or id, e.g. ``body`` or ``a:hover``.
+
+        Such selectors match elements outside any component, so they cannot be
+        scoped to a generated class.
+        """
+        return not any(
+            part.kind in ("class", "id")
+            for compound in self.compounds
+            for part in compound.parts
+        )
+
+    @property
+    def pseudo_selector(self) -> Optional[str]:
+        """Pseudo chain of the owning compound, without its leading colon."""
+        first = self.compounds[0]
+        if first.owner_index() is None:
+            return None
+
+        pseudo = "".join(part.to_css() for part in first.parts if part.is_pseudo)
+        return pseudo[1:] if pseudo else None
+
+    def nested_css(self) -> str:
+        """Render the selector relative to its owner using ``&``.
+
+        ``.card > .title`` becomes ``& > .title`` and ``div.card:hover``
+        becomes ``div&:hover``.
+        """
+        first = self.compounds[0]
+        index = first.owner_index()
+        head = ""
+        tail = ""
+
+        if index is not None:
+            for i, part in enumerate(first.parts):
+                if i == index:
+                    continue
+                # Type selectors must stay in front of the nesting selector
+                if i < index and part.kind in ("type", "universal"):
+                    head += part.to_css()
+                else:
+                    tail += part.to_css()
+
+        rest = [compound.to_css() for compound in self.compounds[1:]]
+        return self._join([f"{head}&{tail}"] + rest)
+
+    def _join(self, rendered) -> str:
+        css = ""
+        for compound, text in zip(self.compounds, rendered):
+            if compound.combinator is None:
+                css += text
+            elif compound.combinator == " ":
+                css += f" {text}"
+            else:
+                css += f" {compound.combinator} {text}"
+        return css
+
+
+def parse_selector(selector: str) -> Selector:
+    """Parse a single complex selector into a Selector.
+
+    Raises ValueError for selector lists or syntax that cannot be parsed.
+    """
+    compounds: List[CompoundSelector] = []
+    parts: List[SimpleSelector] = []
+    combinator: Optional[str] = None
+    pending_combinator: Optional[str] = None
+    pos = 0
+
+    def flush_compound():
+        nonlocal parts, combinator
+        if parts:
+            compounds.append(CompoundSelector(parts=parts, combinator=combinator))
+            parts = []
+            combinator = None
+
+    while pos < len(selector):
+        char = selector[pos]
+
+        if char.isspace() or char in _COMBINATORS:
+            if char in _COMBINATORS:
+                if not parts and not compounds:
+                    raise ValueError(f"Selector starts with combinator: {selector!r}")
+                pending_combinator = char
+            elif parts:
+                pending_combinator = " "
+            flush_compound()
+            pos += 1
+            continue
+
+        # A pending combinator links this compound to the previous one
+        combinator = combinator or pending_combinator
+        pending_combinator = None
+
+        part, pos = _parse_simple_selector(selector, pos)
+        parts.append(part)
+
+    if pending_combinator not in (None, " "):
+        raise ValueError(f"Selector ends with combinator: {selector!r}")
+    flush_compound()
+    if not compounds:
+        raise ValueError("Empty selector")
+
+    return Selector(compounds=compounds)
+
+
+def _parse_simple_selector(selector: str, pos: int) -> Tuple[SimpleSelector, int]:
+    """Parse the simple selector at ``pos``, returning it and the end position."""
+    char = selector[pos]
+
+    if char == ",":
+        raise ValueError(f"Unexpected selector list: {selector!r}")
+    elif char in ".#":
+        match = _IDENT_RE.match(selector, pos + 1)
+        if not match:
+            raise ValueError(f"Expected name after {char!r} in {selector!r}")
+        kind = "class" if char == "." else "id"
+        return SimpleSelector(kind=kind, name=match.group(0)), match.end()
+    elif char == "[":
+        end = _find_closing(selector, pos, "[", "]")
+        name = selector[pos + 1 : end].strip()
+        return SimpleSelector(kind="attribute", name=name), end + 1
+    elif char == ":":
+        return _parse_pseudo_selector(selector, pos)
+    elif char == "*":
+        return SimpleSelector(kind="universal", name="*"), pos + 1
+    elif char == "&":
+        return SimpleSelector(kind="nesting", name="&"), pos + 1
+
+    match = _IDENT_RE.match(selector, pos)
+    if not match:
+        raise ValueError(f"Unexpected {char!r} in selector {selector!r}")
+    return SimpleSelector(kind="type", name=match.group(0)), match.end()
+
+
+def _parse_pseudo_selector(selector: str, pos: int) -> Tuple[SimpleSelector, int]:
+    """Parse a ``:pseudo-class`` or ``::pseudo-element`` with its argument."""
+    kind = "pseudo_class"
+    pos += 1
+    if selector.startswith(":", pos):
+        kind = "pseudo_element"
+        pos += 1
+
+    match = _IDENT_RE.match(selector, pos)
+    if not match:
+        raise ValueError(f"Expected pseudo name in {selector!r}")
+    pos = match.end()
+
+    argument = None
+    if selector.startswith("(", pos):
+        end = _find_closing(selector, pos, "(", ")")
+        argument = selector[pos + 1 : end].strip()
+        pos = end + 1
+
+    return SimpleSelector(kind=kind, name=match.group(0), argument=argument), pos
+
+
+def split_selector_list(selector_list: str) -> List[str]:
+    """Split a comma-separated selector list, ignoring commas in brackets."""
+    selectors = []
+    depth = 0
+    quote = None
+    current = ""
+
+    for i, char in enumerate(selector_list):
+        if quote:
+            if char == quote and selector_list[i - 1] != "\\":
+                quote = None
+        elif char in "\"'":
+            quote = char
+        elif char in "([":
+            depth += 1
+        elif char in ")]":
+            depth = max(0, depth - 1)
+        elif char == "," and depth == 0:
+            selectors.append(current)
+            current = ""
+            continue
+        current += char
+
+    selectors.append(current)
+    return [re.sub(r"\s+", " ", s.strip()) for s in selectors if s.strip()]
+
+
+def and_media_queries(outer: str, inner: str) -> Optional[str]:
+    """Combine two media query lists into one matching where both match.
+
+    Each query of one list is and-ed with each query of the other. Returns
+    ``None`` when a pair can't be written as a single query, as when either
+    negates a media type (``not print``).
+    """
+    queries = []
+    for outer_query in split_selector_list(outer):
+        for inner_query in split_selector_list(inner):
+            query = _and_media_query(outer_query, inner_query)
+            if query is None:
+                return None
+            queries.append(query)
+    return ", ".join(queries)
+
+
+def _and_media_query(outer: str, inner: str) -> Optional[str]:
+    """And two single media queries together."""
+    parts = [_media_query_parts(outer), _media_query_parts(inner)]
+    modifiers = [modifier for modifier, _, _ in parts]
+    if "not" in modifiers:
+        return None
+
+    media_types = {media_type for _, media_type, _ in parts} - {"", "all"}
+    if len(media_types) > 1:
+        # No media is of two types at once
+        return "not all"
+
+    conditions = [condition for _, _, condition in parts if condition]
+    if len(conditions) + len(media_types) > 1:
+        conditions = [_media_operand(condition) for condition in conditions]
+    if media_types:
+        prefix = "only " if "only" in modifiers else ""
+        conditions.insert(0, prefix + media_types.pop())
+    return " and ".join(conditions) or "all"
+
+
+def _media_query_parts(query: str) -> Tuple[str, str, str]:
+    """Split a media query into its modifier, media type and condition."""
+    match = _MEDIA_TYPE_QUERY_RE.match(query.strip())
+    if match is None:
+        return "", "", query.strip()
+    modifier, media_type, condition = match.groups()
+    return (modifier or "").lower(), media_type.lower(), condition or ""
+
+
+def _media_operand(condition: str) -> str:
+    """Parenthesise a media condition joined by ``or`` or negated by ``not``."""
+    top_level = condition
+    while re.search(r"\([^()]*\)", top_level):
+        top_level = re.sub(r"\([^()]*\)", "", top_level)
+    if re.search(r"\b(?:or|not)\b", top_level, re.IGNORECASE):
+        return f"({condition})"
+    return condition
+
+
+def _find_closing(text: str, start: int, opening: str, closing: str) -> int:
+    """Find the index of the bracket closing the one at ``start``."""
+    depth = 0
+    quote = None
+    for i in range(start, len(text)):
+        char = text[i]
+        if quote:
+            if char == quote and text[i - 1] != "\\":
+                quote = None
+        elif char in "\"'":
+            quote = char
+        elif char == opening:
+            depth += 1
+        elif char == closing:
+            depth -= 1
+            if depth == 0:
+                return i
+    raise ValueError(f"Unbalanced {opening!r} in selector {text!r}")
+
+
+@dataclass
+class _Block:
+    """A ``prelude { ... }`` block found while scanning nested CSS."""
+
+    prelude: str
+    body: List[Union[str, "_Block"]]
+    offset: Optional[int] = None
+
+
+class _Statement(str):
+    """A ``;``-terminated statement that remembers where it started."""
+
+    offset: Optional[int] = None
+
+    def __new__(cls, text: str, offset: Optional[int] = None):
+        """Create the statement text with its source offset."""
+        statement = super().__new__(cls, text)
+        statement.offset = offset
+        return statement
+
+
+def _scan_blocks(css: str) -> List[Union[str, _Block]]:
+    """Split CSS into ``;``-terminated statements and nested ``{}`` blocks."""
+    items: List[Union[str, _Block]] = []
+    pos = 0
+    while pos < len(css):
+        body, pos = _scan_block_body(css, pos)
+        items.extend(body)
+    return items
+
+
+def _scan_block_body(css: str, pos: int) -> Tuple[List[Union[str, _Block]], int]:
+    """Scan statements and blocks until the closing brace of the current block."""
+    items: List[Union[str, _Block]] = []
+    buffer = ""
+    start: Optional[int] = None
+    parens = 0
+
+    while pos < len(css):
+        char = css[pos]
+
+        if css.startswith("/*", pos):
+            end = css.find("*/", pos + 2)
+            pos = len(css) if end == -1 else end + 2
+            continue
+
+        if start is None and not char.isspace() and char not in ";{}":
+            start = pos
+
+        end = _verbatim_end(css, pos)
+        if end is not None:
+            buffer += css[pos:end]
+            pos = end
+            continue
+
+        if char in "()":
+            parens = parens + 1 if char == "(" else max(0, parens - 1)
+        elif parens == 0 and char == "{":
+            body, pos = _scan_block_body(css, pos + 1)
+            items.append(_Block(prelude=buffer.strip(), body=body, offset=start))
+            buffer, start = "", None
+            continue
+        elif parens == 0 and char in ";}":
+            _add_statement(items, buffer, start)
+            buffer, start = "", None
+            if char == "}":
+                return items, pos + 1
+            pos += 1
+            continue
+
+        buffer += char
+        pos += 1
+
+    _add_statement(items, buffer, start)
+    return items, pos
+
+
+def _add_statement(items: List[Union[str, _Block]], buffer: str, start: Optional[int]):
+    """Add the buffered statement text, unless it is blank."""
+    if buffer.strip():
+        items.append(_Statement(buffer.strip(), start))
+
+
+def _verbatim_end(css: str, pos: int) -> Optional[int]:
+    """Get the end of a string or SCSS ``#{}`` starting at ``pos``, if any."""
+    char = css[pos]
+    if char in "\"'":
+        end = pos + 1
+        while end < len(css) and css[end] != char:
+            end += 2 if css[end] == "\\" else 1
+        return min(end + 1, len(css))
+
+    if css.startswith("#{", pos):
+        # SCSS interpolation braces never open a block
+        end = css.find("}", pos)
+        return len(css) if end == -1 else end + 1
+
+    return None
+
+
+def _syntax_to_blocks(nodes: List[Node]) -> List[Union[str, _Block]]:
+    """Convert tokenizer output into the statement and block tree."""
+    items: List[Union[str, _Block]] = []
+    for node in nodes:
+        if isinstance(node, Declaration):
+            text = f"{node.name}: {serialize(node.value)}".rstrip()
+            if node.important:
+                text += " !important"
+            items.append(_Statement(text, node.offset))
+        elif isinstance(node, AtRule):
+            prelude = f"@{node.name} {serialize(node.prelude)}".rstrip()
+            if node.content is None:
+                items.append(_Statement(prelude, node.offset))
+            else:
+                body = _syntax_to_blocks(node.content)
+                items.append(_Block(prelude=prelude, body=body, offset=node.offset))
+        else:
+            body = _syntax_to_blocks(node.content)
+            prelude = serialize(node.prelude)
+            items.append(_Block(prelude=prelude, body=body, offset=node.offset))
+    return items
+
+
+def _at_keyword(prelude: str) -> str:
+    """Get the ``@keyword`` a prelude or statement starts with, if any."""
+    match = _AT_KEYWORD_RE.match(prelude)
+    return match.group() if match else ""
+
+
+def _split_important(value: str) -> Tuple[str, bool]:
+    """Split a trailing ``!important`` off a declaration value."""
+    bare = _IMPORTANT_RE.sub("", value)
+    return bare.strip(), bare != value
+
+
+@dataclass
+class CssParam:
+    """A style function parameter supplying a declaration's value."""
+
+    name: str
+    rust_type: str
+
+
+@dataclass
+class CssDeclaration:
+    """A single ``name: value`` declaration of a rule."""
+
+    name: str
+    value: str
+    important: bool = False
+    span: Optional[SourceSpan] = None
+    param: Optional[CssParam] = None
+
 
 @dataclass
 class CssRule:
-    """Represents a parsed CSS rule."""
+    """Represents a parsed CSS rule.
+
+    ``declarations`` keeps every declaration in source order, including
+    repeated fallbacks; ``properties`` is a lookup view where the last
+    declaration of a property wins. Either can be given, the other is derived.
+    """
 
     selector: str
-    properties: Dict[str, str]
+    properties: Dict[str, str] = field(default_factory=dict)
     media_query: Optional[str] = None
+    supports_condition: Optional[str] = None
+    container_query: Optional[str] = None
+    layer: Optional[str] = None
     pseudo_selector: Optional[str] = None
     raw_css: str = ""
+    parsed_selector: Optional[Selector] = None
+    selector_group: Optional[str] = None
+    span: Optional[SourceSpan] = None
+    declarations: List[CssDeclaration] = field(default_factory=list)
+
+    def __post_init__(self):
+        """Derive ``properties`` or ``declarations`` from the other."""
+        if self.declarations:
+            self.properties = {d.name: d.value for d in self.declarations}
+        else:
+            self.declarations = [
+                CssDeclaration(name, value) for name, value in self.properties.items()
+            ]
+
+    @property
+    def full_selector(self) -> str:
+        """The rule's complete selector, pseudo chain included."""
+        if self.parsed_selector is not None:
+            return self.parsed_selector.to_css()
+        if self.pseudo_selector:
+            return f"{self.selector}:{self.pseudo_selector}"
+        return self.selector
 
 
 @dataclass
@@ -24,6 +625,35 @@ class CssKeyframe:
 
     name: str
     keyframes: Dict[str, Dict[str, str]]
+    span: Optional[SourceSpan] = None
+
+
+@dataclass
+class CssFontFace:
+    """Represents an ``@font-face`` rule."""
+
+    declarations: List[CssDeclaration]
+    span: Optional[SourceSpan] = None
+
+
+@dataclass
+class CssVariantSet:
+    """A component's base rules and its variant rules along each axis.
+
+    ``axes`` maps an axis of ``VARIANT_AXES`` (``size``) to the variants
+    found for it (``sm``, ``lg``) and their rules.
+    """
+
+    name: str
+    base: List[CssRule] = field(default_factory=list)
+    axes: Dict[str, Dict[str, List[CssRule]]] = field(default_factory=dict)
+
+
+@dataclass
+class _ImportedRules:
+    """The rules of a file imported in place of an ``@import`` statement."""
+
+    rules: List[CssRule]
 
 
 class CssParser:
@@ -34,21 +664,258 @@ class CssParser:
         self.rules: List[CssRule] = []
         self.keyframes: List[CssKeyframe] = []
         self.imports: List[str] = []
+        self.font_faces: List[CssFontFace] = []
+        self.layers: List[str] = []
+        self.diagnostics: List[Diagnostic] = []
+        self._source = ""
+        self._index: Optional[SourceIndex] = None
+        self._cursor = 0
+        # The file being parsed, and the chain of files importing it
+        self._path: Optional[Path] = None
+        self._import_stack: List[Path] = []
+        self._imported: Set[Path] = set()
+
+    def parse(self, css_content: str, filename: Optional[str] = None) -> List[CssRule]:
+        """Parse CSS content and return structured rules.
+
+        Problems found along the way are collected in ``self.diagnostics``.
+        Imports are only recorded in ``self.imports``; ``parse_file`` follows
+        local ones.
+        """
+        self._reset(css_content, filename)
+        return self._parse_document()
+
+    def _parse_document(self) -> List[CssRule]:
+        """Parse the loaded stylesheet along with the files it imports."""
+        return self._parse_source()
+
+    def _parse_source(self) -> List[CssRule]:
+        """Parse the loaded source, importing local files in place."""
+        css_content = self._source
+        errors: List[ParseError] = []
+        blocks = _syntax_to_blocks(parse_stylesheet(css_content, errors))
+        for error in errors:
+            span = self._span(error.offset)
+            self._report("warning", "syntax-error", error.message, span)
+
+        # cssutils predates CSS Nesting and newer at-rules such as @layer, and
+        # drops the comments @param annotations are written in
+        use_cssutils = not (
+            self._has_nesting(blocks)
+            or self._has_at_rule(blocks, _CSSUTILS_UNSUPPORTED)
+            or _PARAM_COMMENT_RE.search(css_content)
+        )
+        if use_cssutils:
+            try:
+                return self._parse_with_cssutils(css_content)
+            except Exception as e:
+                self._report(
+                    "note",
+                    "parser-fallback",
+                    f"cssutils parsing failed ({e}), falling back to the "
+                    "built-in CSS parser",
+                )
+
+        return self._parse_with_nesting(blocks)
+
+    def parse_file(self, path: str) -> List[CssRule]:
+        """Parse a stylesheet file, along with the local files it imports.
+
+        Imported rules take the place of their ``@import``, under its media
+        condition, and each file is parsed once. Remote imports are left in
+        ``self.imports``; missing files and import cycles are reported.
+        """
+        file_path = Path(path)
+        with open(file_path, "r", encoding="utf-8") as f:
+            self._reset(f.read(), str(file_path))
+        self._path = file_path
+        self._import_stack = [file_path]
+        self._imported = {file_path.resolve()}
+
+        self.rules = self._parse_document()
+        return self.rules
+
+    def _import(self, statement: str) -> List[CssRule]:
+        """Get the rules of the file an ``@import`` statement names."""
+        match = _IMPORT_RE.search(statement)
+        if not match:
+            return []
+        return self._import_href(
+            match.group(1), match.group(2), self._statement_span(statement)
+        )
+
+    def _import_href(
+        self, href: str, media: Optional[str], span: Optional[SourceSpan] = None
+    ) -> List[CssRule]:
+        """Parse an imported file in place, wrapping its rules in ``media``."""
+        if self._path is None or REMOTE_URL_RE.match(href):
+            self.imports.append(href)
+            return []
+
+        media = (media or "").strip()
+        if _IMPORT_CONDITION_RE.match(media):
+            self._report(
+                "warning",
+                "unsupported-import",
+                f"Import with a layer or supports condition skipped: {href}",
+                span,
+            )
+            return []
 
-    def parse(self, css_content: str) -> List[CssRule]:
-        """Parse CSS content and return structured rules."""
+        target = self._import_target(href, span)
+        if target is None:
+            return []
+
+        rules = self._parse_imported_file(target)
+        if media in ("", "all"):
+            return rules
+
+        wrapped = []
+        for rule in rules:
+            media_query = media
+            if rule.media_query:
+                media_query = and_media_queries(media, rule.media_query)
+            if media_query is None:
+                self._report(
+                    "warning",
+                    "unsupported-media",
+                    f"Rule {rule.full_selector} skipped: '{rule.media_query}' "
+                    f"can't be combined with the import's '{media}'",
+                    rule.span,
+                )
+                continue
+            wrapped.append(replace(rule, media_query=media_query))
+        return wrapped
+
+    def _import_target(
+        self, href: str, span: Optional[SourceSpan] = None
+    ) -> Optional[Path]:
+        """Resolve a local import, unless it is missing, cyclic or done already."""
+        target = self._resolve_import(self._path, href)
+        if target.resolve() in (p.resolve() for p in self._import_stack):
+            cycle = " -> ".join(p.name for p in self._import_stack + [target])
+            self._report("warning", "import-cycle", f"Import cycle: {cycle}", span)
+            return None
+        if not target.is_file():
+            self._report(
+                "warning",
+                "import-not-found",
+                f"Imported file not found: {href} (from {self._path})",
+                span,
+            )
+            return None
+        if target.resolve() in self._imported:
+            return None
+        return target
+
+    def _parse_imported_file(self, path: Path) -> List[CssRule]:
+        """Parse an imported file, then return to the importing one.
+
+        The imported file adds to the importer's results, and for SCSS
+        shares its variables and mixins.
+        """
+        with open(path, "r", encoding="utf-8") as f:
+            source = f.read()
+
+        importer = (self._source, self._index, self._cursor, self._path)
+        self._imported.add(path.resolve())
+        self._import_stack.append(path)
+        self._load_source(source, str(path))
+        self._path = path
+        try:
+            return self._parse_source()
+        finally:
+            self._import_stack.pop()
+            self._source, self._index, self._cursor, self._path = importer
+
+    def _resolve_import(self, path: Path, href: str) -> Path:
+        """Resolve a local import, trying SCSS partial names if needed."""
+        target = path.parent / href
+        if target.suffix:
+            return target
+
+        for name in (f"{target.name}.scss", f"_{target.name}.scss"):
+            if (target.parent / name).is_file():
+                return target.parent / name
+        return target.with_name(f"{target.name}.css")
+
+    def _reset(self, source: str, filename: Optional[str]):
+        """Clear results of a previous parse and index the new source."""
         self.rules = []
         self.keyframes = []
         self.imports = []
+        self.font_faces = []
+        self.layers = []
+        self.diagnostics = []
+        self._path = None
+        self._import_stack = []
+        self._imported = set()
+        self._load_source(source, filename)
+
+    def _load_source(self, source: str, filename: Optional[str]):
+        """Index the source positions are reported against."""
+        self._source = source
+        self._index = SourceIndex(source, filename)
+        self._cursor = 0
+
+    def _span(
+        self, start: Optional[int], end: Optional[int] = None
+    ) -> Optional[SourceSpan]:
+        """Get the source span between two offsets, if the start is known."""
+        if self._index is None or start is None:
+            return None
+        return self._index.span(start, end)
+
+    def _report(
+        self,
+        severity: str,
+        code: str,
+        message: str,
+        span: Optional[SourceSpan] = None,
+    ):
+        """Record a diagnostic about the stylesheet being parsed."""
+        self.diagnostics.append(Diagnostic(severity, code, message, span))
+
+    def _locate(
+        self, text: str, names: Iterable[str] = ()
+    ) -> Tuple[Optional[SourceSpan], List[Optional[SourceSpan]]]:
+        """Find where a block starting with ``text`` and its declarations are.
+
+        Used by the cssutils path, which loses source offsets. The
+        search resumes after the previously located block so repeated
+        selectors map to successive occurrences; likewise for declarations.
+        """
+        names = list(names)
+        tokens = text.split()
+        if not tokens:
+            return None, [None] * len(names)
+
+        selector = r"\s*".join(re.escape(token) for token in tokens)
+        pattern = re.compile(rf"({selector})\s*\{{")
+        match = pattern.search(self._source, self._cursor) or pattern.search(
+            self._source
+        )
+        if not match:
+            return None, [None] * len(names)
+
+        end = self._source.find("}", match.end())
+        end = len(self._source) if end == -1 else end + 1
+        self._cursor = end
+
+        spans: List[Optional[SourceSpan]] = []
+        position = match.end()
+        for name in names:
+            declaration = re.compile(
+                rf"(?<![\w-]){re.escape(name)}\s*:[^;}}]*"
+            ).search(self._source, position, end)
+            if not declaration:
+                spans.append(None)
+                continue
+            text_end = declaration.start() + len(declaration.group(0).rstrip())
+            spans.append(self._span(declaration.start(), text_end))
+            position = declaration.end()
 
-        try:
-            # Try using cssutils for robust parsing
-            return self._parse_with_cssutils(css_content)
-        except Exception as e:
-            print(
-                f"Warning: cssutils parsing failed ({e}), falling back to regex parsing"
-            )
-            return self._parse_with_regex(css_content)
+        return self._span(match.start(1), match.end(1)), spans
 
     def _parse_with_cssutils(self, css_content: str) -> List[CssRule]:
         """Parse CSS using the cssutils library."""
@@ -60,9 +927,7 @@ class CssParser:
 
         for rule in sheet:
             if rule.type == rule.STYLE_RULE:
-                css_rule = self._parse_style_rule(rule)
-                if css_rule:
-                    rules.append(css_rule)
+                rules.extend(self._parse_style_rule(rule))
             elif rule.type == rule.MEDIA_RULE:
                 media_rules = self._parse_media_rule(rule)
                 rules.extend(media_rules)
@@ -71,42 +936,132 @@ class CssParser:
                 if keyframe:
                     self.keyframes.append(keyframe)
             elif rule.type == rule.IMPORT_RULE:
-                self.imports.append(rule.href)
+                media = rule.media.mediaText if rule.media is not None else ""
+                rules.extend(self._import_href(rule.href, media))
+            elif rule.type == rule.FONT_FACE_RULE:
+                self._parse_font_face_rule(rule)
 
         return rules
 
-    def _parse_style_rule(self, rule) -> Optional[CssRule]:
-        """Parse a CSS style rule."""
+    def _parse_font_face_rule(self, rule):
+        """Parse a CSS font-face rule."""
+        declarations = [
+            CssDeclaration(prop.name, prop.value, important=bool(prop.priority))
+            for prop in rule.style.getProperties(all=True)
+            if prop.name and prop.value
+        ]
+        if declarations:
+            span, _ = self._locate("@font-face")
+            self.font_faces.append(CssFontFace(declarations, span))
+
+    def _parse_style_rule(self, rule) -> List[CssRule]:
+        """Parse a CSS style rule into one rule per selector in its list."""
         try:
             selector = rule.selectorText.strip()
-            properties = {}
 
-            for prop in rule.style:
-                name = prop.name
-                value = prop.value
-                if name and value:
-                    properties[name] = value
+            # all=True keeps overridden fallbacks such as a repeated `display`
+            declarations = [
+                CssDeclaration(prop.name, prop.value, important=bool(prop.priority))
+                for prop in rule.style.getProperties(all=True)
+                if prop.name and prop.value
+            ]
 
-            if not properties:
-                return None
+            span, spans = self._locate(selector, (d.name for d in declarations))
+            for declaration, declaration_span in zip(declarations, spans):
+                declaration.span = declaration_span
 
-            # Extract pseudo-selector if present
-            pseudo_selector = None
-            if ":" in selector and not selector.startswith(":root"):
-                parts = selector.split(":", 1)
-                if len(parts) == 2:
-                    selector = parts[0].strip()
-                    pseudo_selector = parts[1].strip()
-
-            return CssRule(
-                selector=selector,
-                properties=properties,
-                pseudo_selector=pseudo_selector,
-                raw_css=rule.cssText,
-            )
+            if not declarations:
+                self._report("warning", "empty-rule", f"Empty rule: {selector}", span)
+                return []
+
+            return self._build_rules(selector, declarations, rule.cssText, span=span)
         except Exception as e:
-            print(f"Warning: Failed to parse style rule: {e}")
-            return None
+            self._report("warning", "parse-error", f"Failed to parse style rule: {e}")
+            return []
+
+    def _build_rules(
+        self,
+        selector_text: str,
+        declarations: List[CssDeclaration],
+        raw_css: str,
+        conditions: Optional[Dict[str, str]] = None,
+        span: Optional[SourceSpan] = None,
+    ) -> List[CssRule]:
+        """Create one rule per selector of a (possibly comma-separated) list.
+
+        ``conditions`` maps ``GROUPING_AT_RULES`` fields to the preludes of
+        the at-rules wrapping the rule.
+        """
+        selectors = split_selector_list(selector_text)
+        selector_group = ", ".join(selectors) if len(selectors) > 1 else None
+        rules = []
+
+        for selector_str in selectors:
+            selector, pseudo_selector, parsed = self._resolve_selector(selector_str)
+            if parsed is None:
+                self._report(
+                    "warning",
+                    "invalid-selector",
+                    f"Could not parse selector '{selector_str.strip()}', "
+                    "keeping it as written",
+                    span,
+                )
+            rules.append(
+                CssRule(
+                    selector=selector,
+                    declarations=list(declarations),
+                    pseudo_selector=pseudo_selector,
+                    raw_css=raw_css,
+                    parsed_selector=parsed,
+                    selector_group=selector_group,
+                    span=span,
+                    **(conditions or {}),
+                )
+            )
+
+        return rules
+
+    def _resolve_selector(
+        self, selector: str
+    ) -> Tuple[str, Optional[str], Optional[Selector]]:
+        """Resolve the owning selector, pseudo chain and parsed selector."""
+        try:
+            parsed = parse_selector(selector.strip())
+        except ValueError:
+            # Keep unparseable selectors, splitting off the pseudo chain only
+            base, pseudo = self._split_pseudo_selector(selector)
+            return base, pseudo, None
+
+        return parsed.owner, parsed.pseudo_selector, parsed
+
+    def _split_pseudo_selector(self, selector: str) -> Tuple[str, Optional[str]]:
+        """Split selector into base and pseudo chain (without leading colon).
+
+        ``.button::before`` yields ``(".button", ":before")``; colons inside
+        attribute selectors, functional arguments and strings are ignored.
+        """
+        selector = selector.strip()
+        if selector.startswith(":root"):
+            return selector, None
+
+        depth = 0
+        quote = None
+        for i, char in enumerate(selector):
+            if quote:
+                if char == quote and selector[i - 1] != "\\":
+                    quote = None
+            elif char in "\"'":
+                quote = char
+            elif char in "([":
+                depth += 1
+            elif char in ")]":
+                depth = max(0, depth - 1)
+            elif char == ":" and depth == 0:
+                base = selector[:i].strip()
+                pseudo = re.sub(r"\s+", " ", selector[i + 1 :].strip())
+                return base, pseudo or None
+
+        return selector, None
 
     def _parse_media_rule(self, rule) -> List[CssRule]:
         """Parse a CSS media rule."""
@@ -115,8 +1070,7 @@ class CssParser:
 
         for nested_rule in rule:
             if nested_rule.type == nested_rule.STYLE_RULE:
-                css_rule = self._parse_style_rule(nested_rule)
-                if css_rule:
+                for css_rule in self._parse_style_rule(nested_rule):
                     css_rule.media_query = media_query
                     rules.append(css_rule)
 
@@ -130,132 +1084,354 @@ class CssParser:
 
             for keyframe_rule in rule:
                 key = keyframe_rule.keyText
-                properties = {}
-
-                for prop in keyframe_rule.style:
-                    properties[prop.name] = prop.value
+                properties = {prop.name: prop.value for prop in keyframe_rule.style}
 
                 if properties:
                     keyframes[key] = properties
 
-            return CssKeyframe(name=name, keyframes=keyframes)
+            span, _ = self._locate(f"@keyframes {name}")
+            return CssKeyframe(name=name, keyframes=keyframes, span=span)
         except Exception as e:
-            print(f"Warning: Failed to parse keyframes rule: {e}")
+            self._report(
+                "warning", "parse-error", f"Failed to parse keyframes rule: {e}"
+            )
             return None
 
-    def _parse_with_regex(self, css_content: str) -> List[CssRule]:
-        """Fallback CSS parsing using regex."""
-        rules = []
+    def _has_nesting(self, items: List[Union[str, _Block]]) -> bool:
+        """Check whether any style rule contains nested rules."""
+        for item in items:
+            if not isinstance(item, _Block):
+                continue
 
-        # Remove comments
-        css_content = re.sub(r"/\*.*?\*/", "", css_content, flags=re.DOTALL)
+            if item.prelude.startswith("@"):
+                if "keyframes" not in item.prelude and self._has_nesting(item.body):
+                    return True
+            elif any(isinstance(child, _Block) for child in item.body):
+                return True
+
+        return False
+
+    def _has_at_rule(
+        self, items: List[Union[str, _Block]], names: Tuple[str, ...]
+    ) -> bool:
+        """Check whether any of the named at-rules appears at any depth."""
+        for item in items:
+            if not isinstance(item, _Block):
+                if _at_keyword(item) in names:
+                    return True
+            elif _at_keyword(item.prelude) in names or self._has_at_rule(
+                item.body, names
+            ):
+                return True
+
+        return False
+
+    def _parse_with_nesting(self, items: List[Union[str, _Block]]) -> List[CssRule]:
+        """Parse a scanned block tree, flattening nested rules."""
+        rules: List[CssRule] = []
+        self._flatten_nested(items, [], {}, rules)
+        return rules
 
-        # Extract media queries
-        media_pattern = r"@media\s+([^{]+)\s*\{([^{}]*(?:\{[^{}]*\}[^{}]*)*)\}"
-        media_matches = re.findall(media_pattern, css_content, re.DOTALL)
+    def _flatten_nested(
+        self,
+        items: List[Union[str, _Block]],
+        parents: List[str],
+        conditions: Dict[str, str],
+        rules: List[CssRule],
+    ):
+        """Flatten nested blocks into rules with fully resolved selectors."""
+        for item in items:
+            if not isinstance(item, _Block):
+                self._flatten_statement(item, parents, conditions, rules)
+                continue
 
-        for media_query, media_content in media_matches:
-            media_rules = self._parse_rules_content(
-                media_content.strip(), media_query.strip()
+            prelude = item.prelude
+            keyword = _at_keyword(prelude)
+            if keyword in GROUPING_AT_RULES:
+                nested = self._nest_condition(conditions, keyword, item)
+                if nested is not None:
+                    self._add_nested_rules(
+                        parents, item.body, nested, rules, item.offset
+                    )
+                    self._flatten_nested(item.body, parents, nested, rules)
+            elif prelude.startswith("@") and "keyframes" in prelude:
+                self._add_nested_keyframes(
+                    prelude.split(None, 1)[-1], item.body, item.offset
+                )
+            elif keyword == "@font-face":
+                self._add_font_face(item)
+            elif not prelude.startswith("@"):
+                selectors = self._resolve_nested_selectors(parents, prelude)
+                if not item.body:
+                    self._report(
+                        "warning",
+                        "empty-rule",
+                        f"Empty rule: {prelude}",
+                        self._span(item.offset, self._prelude_end(item.offset)),
+                    )
+                self._add_nested_rules(
+                    selectors, item.body, conditions, rules, item.offset
+                )
+                self._flatten_nested(item.body, selectors, conditions, rules)
+
+    def _flatten_statement(
+        self,
+        statement: Union[str, _ImportedRules],
+        parents: List[str],
+        conditions: Dict[str, str],
+        rules: List[CssRule],
+    ):
+        """Handle a statement outside any declaration block.
+
+        Top-level imports are replaced by the rules of the file they name.
+        """
+        if isinstance(statement, _ImportedRules):
+            rules.extend(statement.rules)
+        elif parents:
+            return
+        elif statement.startswith("@import") and not conditions:
+            rules.extend(self._import(statement))
+        else:
+            self._record_statement(statement)
+
+    def _record_statement(self, statement: str):
+        """Record the layer order declared by top-level statements."""
+        if _at_keyword(statement) == "@layer":
+            for name in statement[len("@layer") :].split(","):
+                self._add_layer(name.strip())
+
+    def _add_layer(self, name: str):
+        """Record a cascade layer the first time it is declared or used."""
+        if name and name not in self.layers:
+            self.layers.append(name)
+
+    def _nest_condition(
+        self, conditions: Dict[str, str], keyword: str, block: _Block
+    ) -> Optional[Dict[str, str]]:
+        """Add a grouping at-rule, combining it with an enclosing one.
+
+        Conditions are and-ed and layer names joined into ``outer.inner``. A
+        nested container query naming its own container queries a different
+        element, so it replaces the enclosing one instead. Returns ``None``
+        for media queries that can't be combined, whose rules are skipped.
+        """
+        field_name = GROUPING_AT_RULES[keyword]
+        condition = block.prelude[len(keyword) :].strip()
+        outer = conditions.get(field_name)
+        if keyword == "@layer":
+            condition = f"{outer}.{condition}" if outer else condition
+            self._add_layer(condition)
+        elif outer and keyword == "@container" and _CONTAINER_NAME_RE.match(condition):
+            self._report(
+                "warning",
+                "nested-container",
+                f"Container query '{condition}' replaces the enclosing '{outer}'",
+                self._span(block.offset, self._prelude_end(block.offset)),
             )
-            rules.extend(media_rules)
-            # Remove processed media query from content
-            css_content = css_content.replace(
-                f"@media {media_query} {{{media_content}}}", ""
+        elif outer and keyword == "@media":
+            combined = and_media_queries(outer, condition)
+            if combined is None:
+                self._report(
+                    "warning",
+                    "unsupported-media",
+                    f"Media query '{condition}' can't be combined with the "
+                    f"enclosing '{outer}'; its rules are skipped",
+                    self._span(block.offset, self._prelude_end(block.offset)),
+                )
+                return None
+            condition = combined
+        elif outer:
+            condition = f"{outer} and {condition}"
+        return {**conditions, field_name: condition}
+
+    def _resolve_nested_selectors(self, parents: List[str], prelude: str) -> List[str]:
+        """Resolve nested selectors against their parents, replacing ``&``."""
+        children = split_selector_list(prelude)
+        if not parents:
+            return children
+
+        resolved = []
+        for parent in parents:
+            for child in children:
+                if "&" in child:
+                    resolved.append(child.replace("&", parent))
+                else:
+                    # Relative selectors (``.title``, ``> .body``) are descendants
+                    resolved.append(f"{parent} {child}")
+
+        return resolved
+
+    def _add_nested_rules(
+        self,
+        selectors: List[str],
+        body: List[Union[str, _Block]],
+        conditions: Dict[str, str],
+        rules: List[CssRule],
+        offset: Optional[int] = None,
+    ):
+        """Create rules for the declarations placed directly inside a block."""
+        statements = [item for item in body if isinstance(item, str)]
+        declarations = self._parse_declarations(statements)
+        if not selectors or not declarations:
+            return
+
+        selector_text = ", ".join(selectors)
+        raw_css = f"{selector_text} {{ {'; '.join(statements)} }}"
+        rules.extend(
+            self._build_rules(
+                selector_text,
+                declarations,
+                raw_css,
+                conditions,
+                span=self._span(offset, self._prelude_end(offset)),
             )
+        )
 
-        # Extract keyframes
-        keyframe_pattern = r"@keyframes\s+([^{]+)\s*\{([^{}]*(?:\{[^{}]*\}[^{}]*)*)\}"
-        keyframe_matches = re.findall(keyframe_pattern, css_content, re.DOTALL)
+    def _add_nested_keyframes(
+        self, name: str, body: List[Union[str, _Block]], offset: Optional[int] = None
+    ):
+        """Collect a keyframes block found by the nesting scanner."""
+        keyframes = {}
+        for item in body:
+            if isinstance(item, _Block):
+                declarations = self._parse_declarations(
+                    [child for child in item.body if isinstance(child, str)]
+                )
+                if declarations:
+                    keyframes[item.prelude] = {d.name: d.value for d in declarations}
 
-        for name, keyframe_content in keyframe_matches:
-            self._parse_keyframes_content(name.strip(), keyframe_content.strip())
-            # Remove processed keyframes from content
-            css_content = css_content.replace(
-                f"@keyframes {name} {{{keyframe_content}}}", ""
+        if keyframes:
+            span = self._span(offset, self._prelude_end(offset))
+            self.keyframes.append(
+                CssKeyframe(name=name.strip(), keyframes=keyframes, span=span)
             )
 
-        # Parse regular rules
-        regular_rules = self._parse_rules_content(css_content)
-        rules.extend(regular_rules)
-
-        return rules
-
-    def _parse_rules_content(
-        self, content: str, media_query: Optional[str] = None
-    ) -> List[CssRule]:
-        """Parse CSS rules from content string."""
-        rules = []
-
-        # Pattern to match CSS rules: selector { properties }
-        rule_pattern = r"([^{]+)\{([^}]+)\}"
-        rule_matches = re.findall(rule_pattern, content)
-
-        for selector, properties_str in rule_matches:
-            selector = selector.strip()
-            if not selector:
-                continue
-
-            properties = self._parse_properties_string(properties_str)
-            if not properties:
+    def _add_font_face(self, block: _Block):
+        """Collect an ``@font-face`` block found by the nesting scanner."""
+        declarations = self._parse_declarations(
+            [item for item in block.body if isinstance(item, str)]
+        )
+        if declarations:
+            span = self._span(block.offset, self._prelude_end(block.offset))
+            self.font_faces.append(CssFontFace(declarations, span))
+
+    def _prelude_end(self, offset: Optional[int]) -> Optional[int]:
+        """Get the offset where the prelude starting at ``offset`` ends."""
+        if offset is None:
+            return None
+        end = self._source.find("{", offset)
+        end = len(self._source) if end == -1 else end
+        return len(self._source[:end].rstrip())
+
+    def _parse_declarations(self, statements: List[str]) -> List[CssDeclaration]:
+        """Parse ``name: value`` statements into declarations, in order."""
+        declarations = []
+        for index, statement in enumerate(statements):
+            if statement.startswith("@"):
                 continue
+            name, _, value = statement.partition(":")
+            span = self._statement_span(statement)
+            name = name.strip()
+            value, important = _split_important(value)
+            if name and value:
+                param = self._find_param(statement, statements[index + 1 :])
+                declarations.append(
+                    CssDeclaration(name, value, important, span, param)
+                )
+            else:
+                self._report(
+                    "warning",
+                    "empty-declaration",
+                    f"Declaration without a value skipped: {statement}",
+                    span,
+                )
+        return declarations
+
+    def _find_param(
+        self, statement: str, following: List[str]
+    ) -> Optional[CssParam]:
+        """Find the ``@param`` comment after a statement, on its line.
+
+        The search stops at the next statement, so a comment belongs to the
+        last declaration before it. Names that aren't valid Rust identifiers,
+        such as ``type``, are sanitised like function names.
+        """
+        offset = getattr(statement, "offset", None)
+        if offset is None:
+            return None
 
-            # Extract pseudo-selector
-            pseudo_selector = None
-            if ":" in selector and not selector.startswith(":root"):
-                parts = selector.split(":", 1)
-                if len(parts) == 2:
-                    selector = parts[0].strip()
-                    pseudo_selector = parts[1].strip()
-
-            rule = CssRule(
-                selector=selector,
-                properties=properties,
-                media_query=media_query,
-                pseudo_selector=pseudo_selector,
-                raw_css=f"{selector} {{ {properties_str} }}",
-            )
-            rules.append(rule)
+        end = self._source.find("\n", offset)
+        end = len(self._source) if end == -1 else end
+        for other in following:
+            other_offset = getattr(other, "offset", None)
+            if other_offset is not None:
+                end = min(end, other_offset)
+                break
 
-        return rules
+        match = _PARAM_COMMENT_RE.search(self._source, offset, end)
+        if match is None:
+            return None
+        return CssParam(sanitize_rust_identifier(match.group(1)), match.group(2))
 
-    def _parse_properties_string(self, properties_str: str) -> Dict[str, str]:
-        """Parse CSS properties from a string."""
-        properties = {}
+    def _statement_span(self, statement: str) -> Optional[SourceSpan]:
+        """Get the span of a scanned statement, if it carries an offset."""
+        offset = getattr(statement, "offset", None)
+        if offset is None:
+            return None
+        return self._span(offset, offset + len(statement))
 
-        # Split by semicolon and parse each property
-        for prop_str in properties_str.split(";"):
-            prop_str = prop_str.strip()
-            if not prop_str:
-                continue
+    def is_global_rule(self, rule: CssRule) -> bool:
+        """Check whether a rule styles global elements rather than a component."""
+        if rule.parsed_selector is not None:
+            return rule.parsed_selector.is_global
+        return not re.search(r"[.#]", rule.selector)
 
-            if ":" in prop_str:
-                name, value = prop_str.split(":", 1)
-                name = name.strip()
-                value = value.strip()
+    def split_global_rules(
+        self, rules: List[CssRule]
+    ) -> Tuple[List[CssRule], List[CssRule]]:
+        """Split rules into global rules and component rules, keeping order."""
+        global_rules = [rule for rule in rules if self.is_global_rule(rule)]
+        component_rules = [rule for rule in rules if not self.is_global_rule(rule)]
+        return global_rules, component_rules
 
-                if name and value:
-                    properties[name] = value
+    def split_theme_tokens(
+        self, rules: List[CssRule]
+    ) -> Tuple[Dict[str, Dict[str, str]], List[CssRule]]:
+        """Split custom properties defining theme tokens off the rules.
 
-        return properties
+        Tokens come from ``:root`` (the ``default`` theme) and from
+        ``[data-theme=name]`` overrides, outside any at-rule. Returns the
+        tokens per theme and the rules left once they are removed.
+        """
+        themes: Dict[str, Dict[str, str]] = {}
+        remaining = []
 
-    def _parse_keyframes_content(self, name: str, content: str):
-        """Parse keyframes content."""
-        keyframes = {}
+        for rule in rules:
+            theme = self._theme_name(rule)
+            tokens = [d for d in rule.declarations if d.name.startswith("--")]
+            if theme is None or not tokens:
+                remaining.append(rule)
+                continue
 
-        # Pattern to match keyframe rules: percentage { properties }
-        keyframe_pattern = r"([^{]+)\{([^}]+)\}"
-        keyframe_matches = re.findall(keyframe_pattern, content)
+            themes.setdefault(theme, {}).update((d.name, d.value) for d in tokens)
+            declarations = [d for d in rule.declarations if d not in tokens]
+            if declarations:
+                remaining.append(
+                    replace(rule, properties={}, declarations=declarations)
+                )
 
-        for key, properties_str in keyframe_matches:
-            key = key.strip()
-            properties = self._parse_properties_string(properties_str)
+        return themes, remaining
 
-            if properties:
-                keyframes[key] = properties
+    def _theme_name(self, rule: CssRule) -> Optional[str]:
+        """Get the theme a rule's selector defines tokens for, if any."""
+        if any(getattr(rule, name) for name in GROUPING_AT_RULES.values()):
+            return None
 
-        if keyframes:
-            self.keyframes.append(CssKeyframe(name=name, keyframes=keyframes))
+        selector = re.sub(r"\s+", "", rule.full_selector)
+        if selector in (":root", "html"):
+            return "default"
+        match = _THEME_SELECTOR_RE.match(selector)
+        return match.group(2) if match else None
 
     def group_rules_by_component(
         self, rules: List[CssRule]
@@ -342,22 +1518,355 @@ class CssParser:
 
         return variants
 
+    def extract_variant_sets(
+        self, rules: List[CssRule]
+    ) -> Tuple[List[CssVariantSet], List[CssRule]]:
+        """Group rules into components with variant axes, for enum output.
+
+        Rules varying a base along a known axis (``btn-primary``, ``btn-sm``)
+        join their base's set, as do the base's own rules. Returns the sets
+        and the rules that belong to none. Bases naming the same component
+        (``btn`` and ``button``) share one set.
+        """
+        sets: Dict[str, CssVariantSet] = {}
+        owners: Dict[str, str] = {}
+        split = [(rule, self._split_variant_axis(rule.selector)) for rule in rules]
+
+        for rule, (base, axis, variant) in split:
+            if axis is not None:
+                name = owners.setdefault(base, self._extract_component_name(base))
+                variant_set = sets.setdefault(name, CssVariantSet(name))
+                variants = variant_set.axes.setdefault(axis, {})
+                variants.setdefault(variant, []).append(rule)
+
+        remaining = []
+        for rule, (base, axis, _) in split:
+            name = owners.get(base, base)
+            if axis is None and name in sets:
+                sets[name].base.append(rule)
+            elif axis is None:
+                remaining.append(rule)
+
+        for variant_set in sets.values():
+            variant_set.axes = {
+                axis: variant_set.axes[axis]
+                for axis in VARIANT_AXES
+                if axis in variant_set.axes
+            }
+
+        return list(sets.values()), remaining
+
     def _split_variant_name(self, selector: str) -> Tuple[str, Optional[str]]:
         """Split selector into base name and variant name."""
+        base_name, axis, variant_name = self._split_variant_axis(selector)
+        if axis is not None:
+            return base_name, variant_name
+
+        match = re.match(r"([a-zA-Z]+)[-_]([a-zA-Z]+)", selector.lstrip(". "))
+        if match:
+            return match.group(1).lower(), match.group(2).lower()
+
+        return base_name, None
+
+    def _split_variant_axis(
+        self, selector: str
+    ) -> Tuple[str, Optional[str], Optional[str]]:
+        """Split selector into base name, variant axis and variant name."""
         # Remove leading dots and clean
         clean_selector = selector.lstrip(". ")
 
-        # Common variant patterns
-        variant_patterns = [
-            r"([a-zA-Z]+)[-_](primary|secondary|success|danger|warning|info|light|dark)",
-            r"([a-zA-Z]+)[-_](small|sm|large|lg|xl|xs)",
-            r"([a-zA-Z]+)[-_](outline|solid|ghost|link)",
-            r"([a-zA-Z]+)[-_]([a-zA-Z]+)",
-        ]
-
-        for pattern in variant_patterns:
-            match = re.match(pattern, clean_selector, re.IGNORECASE)
+        for axis, pattern in VARIANT_AXES.items():
+            # The variant must be a whole token, not the start of a longer
+            # word (``btn-smooth`` isn't a small ``btn``)
+            match = re.match(
+                rf"([a-zA-Z]+)[-_]({pattern})(?![a-zA-Z0-9])",
+                clean_selector,
+                re.IGNORECASE,
+            )
             if match:
-                return match.group(1).lower(), match.group(2).lower()
+                return match.group(1).lower(), axis, match.group(2).lower()
+
+        return clean_selector.lower(), None, None
+
+
+class ScssParser(CssParser):
+    """Parses SCSS into the same rule model as CssParser.
+
+    Supports ``$variables``, nesting with ``&`` (including ``&-suffix``),
+    ``@mixin``/``@include`` (with ``@content``) and ``@extend``.
+    """
+
+    def __init__(self):
+        """Initialize the SCSS parser."""
+        super().__init__()
+        self.variables: Dict[str, str] = {}
+        self.mixins: Dict[str, Tuple[List[Tuple[str, Optional[str]]], list]] = {}
+        self._extends: List[Tuple[List[str], str]] = []
+
+    def _reset(self, source: str, filename: Optional[str]):
+        """Clear results, variables and mixins of a previous parse."""
+        super()._reset(source, filename)
+        self.variables = {}
+        self.mixins = {}
+        self._extends = []
+
+    def _load_source(self, source: str, filename: Optional[str]):
+        """Index the source with its ``//`` comments blanked out."""
+        super()._load_source(self._strip_line_comments(source), filename)
+
+    def _parse_document(self) -> List[CssRule]:
+        """Parse the loaded SCSS and its imports, then apply ``@extend``."""
+        rules = self._apply_extends(self._parse_source())
+
+        # Placeholder selectors only exist to be extended
+        return [rule for rule in rules if not rule.selector.startswith("%")]
+
+    def _parse_source(self) -> List[CssRule]:
+        """Expand the loaded SCSS in the top-level scope and parse the result."""
+        blocks = _scan_blocks(self._source)
+        return self._parse_with_nesting(self._expand_scss(blocks, self.variables))
+
+    def _strip_line_comments(self, scss_content: str) -> str:
+        """Blank out ``//`` comments, leaving strings and ``url(...)`` intact.
+
+        Comments are replaced by spaces so source offsets stay valid.
+        """
+        result = []
+        quote = None
+        parens = 0
+        pos = 0
+
+        while pos < len(scss_content):
+            char = scss_content[pos]
+            if quote:
+                if char == quote and scss_content[pos - 1] != "\\":
+                    quote = None
+            elif char in "\"'":
+                quote = char
+            elif char == "(":
+                parens += 1
+            elif char == ")":
+                parens = max(0, parens - 1)
+            elif scss_content.startswith("/*", pos):
+                end = scss_content.find("*/", pos + 2)
+                end = len(scss_content) if end == -1 else end + 2
+                result.append(scss_content[pos:end])
+                pos = end
+                continue
+            elif scss_content.startswith("//", pos) and parens == 0:
+                end = scss_content.find("\n", pos)
+                end = len(scss_content) if end == -1 else end
+                result.append(" " * (end - pos))
+                pos = end
+                continue
+
+            result.append(char)
+            pos += 1
+
+        return "".join(result)
+
+    def _expand_scss(
+        self,
+        items: List[Union[str, _Block]],
+        scope: Dict[str, str],
+        content: Optional[List[Union[str, _Block]]] = None,
+    ) -> List[Union[str, _Block]]:
+        """Resolve variables, mixins and includes into plain nested CSS."""
+        expanded: List[Union[str, _Block]] = []
+
+        for item in items:
+            if isinstance(item, _Block):
+                expanded.extend(self._expand_block(item, scope, content))
+                continue
+
+            variable = _SCSS_VARIABLE_RE.match(item)
+            if variable:
+                self._assign_variable(*variable.groups(), scope)
+            elif item.startswith("@include"):
+                expanded.extend(
+                    self._include(
+                        item[len("@include") :],
+                        scope,
+                        span=self._statement_span(item),
+                    )
+                )
+            elif item == "@content":
+                expanded.extend(content or [])
+            elif item.startswith("@import") and scope is self.variables:
+                # Top-level imports share the importing file's scope
+                expanded.append(_ImportedRules(self._import(item)))
+            else:
+                expanded.append(self._substitute(item, scope))
+
+        return expanded
+
+    def _expand_block(
+        self,
+        block: _Block,
+        scope: Dict[str, str],
+        content: Optional[List[Union[str, _Block]]] = None,
+    ) -> List[Union[str, _Block]]:
+        """Expand one block: a mixin definition, an include or a nested rule."""
+        prelude = self._substitute(block.prelude, scope, selector=True)
+        if not prelude:
+            return []
+
+        span = self._span(block.offset, self._prelude_end(block.offset))
+        if prelude.startswith("@mixin"):
+            self._define_mixin(prelude[len("@mixin") :], block.body, span)
+        elif prelude.startswith("@include"):
+            return self._include(prelude[len("@include") :], scope, block.body, span)
+        elif prelude.split(None, 1)[0] in _SCSS_UNSUPPORTED:
+            self._report(
+                "warning",
+                "unsupported-directive",
+                f"Unsupported SCSS directive skipped: {prelude}",
+                span,
+            )
+        else:
+            body = self._expand_scss(block.body, dict(scope), content)
+            return [_Block(prelude=prelude, body=body, offset=block.offset)]
+
+        return []
+
+    def _assign_variable(
+        self, name: str, value: str, flag: Optional[str], scope: Dict[str, str]
+    ):
+        """Assign a ``$variable``, honouring ``!default`` and ``!global``."""
+        if flag == "!default" and name in scope:
+            return
+        scope[name] = self._substitute(value, scope)
+        if flag == "!global":
+            self.variables[name] = scope[name]
+
+    def _define_mixin(
+        self,
+        signature: str,
+        body: List[Union[str, _Block]],
+        span: Optional[SourceSpan] = None,
+    ):
+        """Register a ``@mixin`` with its parameters and default values."""
+        match = _SCSS_CALL_RE.match(signature)
+        if not match:
+            self._report(
+                "warning",
+                "invalid-mixin",
+                f"Invalid mixin signature: @mixin{signature}",
+                span,
+            )
+            return
+
+        params = []
+        for param in split_selector_list(match.group(2) or ""):
+            name, _, default = param.partition(":")
+            params.append((name.strip().lstrip("$"), default.strip() or None))
+
+        self.mixins[match.group(1)] = (params, body)
+
+    def _include(
+        self,
+        call: str,
+        scope: Dict[str, str],
+        content: Optional[List[Union[str, _Block]]] = None,
+        span: Optional[SourceSpan] = None,
+    ) -> List[Union[str, _Block]]:
+        """Expand an ``@include`` with positional and keyword arguments."""
+        match = _SCSS_CALL_RE.match(call)
+        if not match or match.group(1) not in self.mixins:
+            self._report(
+                "warning",
+                "unknown-mixin",
+                f"Unknown mixin included: @include{call}",
+                span,
+            )
+            return []
+
+        params, body = self.mixins[match.group(1)]
+        positional = []
+        keywords = {}
+        for arg in split_selector_list(match.group(2) or ""):
+            keyword = _SCSS_VARIABLE_RE.match(arg)
+            if keyword:
+                keywords[keyword.group(1)] = self._substitute(keyword.group(2), scope)
+            else:
+                positional.append(self._substitute(arg, scope))
+
+        mixin_scope = dict(self.variables)
+        for index, (name, default) in enumerate(params):
+            if index < len(positional):
+                mixin_scope[name] = positional[index]
+            elif name in keywords:
+                mixin_scope[name] = keywords[name]
+            elif default is not None:
+                mixin_scope[name] = self._substitute(default, mixin_scope)
+
+        if content is not None:
+            content = self._expand_scss(content, dict(scope))
+
+        return self._expand_scss(body, mixin_scope, content)
+
+    def _substitute(
+        self, text: str, scope: Dict[str, str], selector: bool = False
+    ) -> str:
+        """Replace ``#{...}`` interpolation and ``$variable`` references."""
+
+        def lookup(match):
+            return scope.get(match.group(1), match.group(0))
+
+        offset = getattr(text, "offset", None)
+        text = re.sub(r"#\{\s*\$([\w-]+)\s*\}", lookup, text)
+
+        # Selectors only support interpolation; at-rule preludes take variables
+        if not selector or text.startswith("@"):
+            text = re.sub(r"\$([\w-]+)", lookup, text)
+
+        return _Statement(text, offset)
+
+    def _add_nested_rules(
+        self,
+        selectors: List[str],
+        body: List[Union[str, _Block]],
+        conditions: Dict[str, str],
+        rules: List[CssRule],
+        offset: Optional[int] = None,
+    ):
+        """Record ``@extend`` statements before creating the block's rules."""
+        for item in body:
+            if isinstance(item, str) and item.startswith("@extend"):
+                target = item[len("@extend") :].replace("!optional", "").strip()
+                self._extends.append((selectors, target))
+
+        super()._add_nested_rules(selectors, body, conditions, rules, offset)
+
+    def _apply_extends(self, rules: List[CssRule]) -> List[CssRule]:
+        """Add extending selectors right after every rule they extend."""
+        if not self._extends:
+            return rules
+
+        result = []
+        for rule in rules:
+            result.append(rule)
+            selector = rule.full_selector
+
+            for extenders, target in self._extends:
+                suffix = selector[len(target) :]
+                if not selector.startswith(target) or suffix[:1] not in ("", ":"):
+                    continue
+
+                extended = ", ".join(f"{extender}{suffix}" for extender in extenders)
+                result.extend(
+                    self._build_rules(
+                        extended,
+                        rule.declarations,
+                        f"{extended} {{ /* @extend {target} */ }}",
+                        {
+                            field_name: getattr(rule, field_name)
+                            for field_name in GROUPING_AT_RULES.values()
+                            if getattr(rule, field_name)
+                        },
+                        span=rule.span,
+                    )
+                )
+
+        return result
 
-        return clean_selector.lower(), None
@@ -0,0 +1,62 @@
+"""Framework targets for the generated Rust code."""
+
+from typing import Dict, List, Type
+
+
+class Target:
+    """The framework-specific parts of the generated Rust code.
+
+    Every target shares the parsed rules and the rendered CSS. Stylist targets
+    build style functions around ``stylist::Style``; the others scope each
+    function's CSS to a generated class name and mount it with a component.
+    The code itself comes from the templates, which ``name`` selects.
+    """
+
+    name = ""
+    uses_stylist = False
+    # The imports of the scoped style components
+    imports: List[str] = []
+    # The imports of the code declaring a theme's tokens on :root
+    theme_imports: List[str] = []
+    # What the global styles module offers for mounting its styles
+    global_styles = "GlobalStyles"
+
+
+class YewTarget(Target):
+    """Yew with stylist, whose style functions return ``stylist::Style``."""
+
+    name = "yew"
+    uses_stylist = True
+    theme_imports = ["use stylist::GlobalStyle;"]
+    global_styles = "global_styles()"
+
+
+class LeptosTarget(Target):
+    """Leptos, mounting styles with ``leptos_meta``'s ``<Style>``."""
+
+    name = "leptos"
+    imports = ["use leptos::prelude::*;", "use leptos_meta::Style;"]
+    theme_imports = imports
+
+
+class DioxusTarget(Target):
+    """Dioxus, mounting styles with a ``style`` element."""
+
+    name = "dioxus"
+    imports = ["use dioxus::prelude::*;"]
+    theme_imports = imports
+
+
+TARGETS: Dict[str, Type[Target]] = {
+    target.name: target for target in (YewTarget, LeptosTarget, DioxusTarget)
+}
+
+
+def get_target(name: str) -> Target:
+    """Get the target for a framework name such as ``yew``."""
+    try:
+        return TARGETS[name]()
+    except KeyError:
+        raise ValueError(
+            f"Unknown target {name!r}, expected one of: {', '.join(TARGETS)}"
+        )
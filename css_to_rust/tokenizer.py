@@ -0,0 +1,669 @@
+"""CSS Syntax Level 3 tokenizer and parser for CSS to Rust conversion.
+
+Implements the tokenization and parsing algorithms of
+https://www.w3.org/TR/css-syntax-3/, including nested rules inside style
+blocks. Every token and node keeps its offset into the source, and the raw
+source text of tokens is kept so values round-trip unchanged.
+"""
+
+from dataclasses import dataclass
+from typing import List, Optional, Tuple, Union
+
+_NEWLINES = "\n\r\f"
+
+_WHITESPACE = " \t\n\r\f"
+
+_HEX_DIGITS = "0123456789abcdefABCDEF"
+
+_PUNCTUATION = {
+    ":": "colon",
+    ";": "semicolon",
+    ",": "comma",
+    "(": "(",
+    ")": ")",
+    "[": "[",
+    "]": "]",
+    "{": "{",
+    "}": "}",
+}
+
+_CLOSING = {"{": "}", "[": "]", "(": ")"}
+
+
+@dataclass
+class Token:
+    """A single CSS token."""
+
+    type: str
+    value: Union[str, float, None]
+    text: str
+    offset: int
+    unit: Optional[str] = None
+    is_id: bool = False
+
+    def __str__(self) -> str:
+        """Serialize the token as written in the source."""
+        return self.text
+
+
+@dataclass
+class Block:
+    """A ``{}``, ``[]`` or ``()`` simple block."""
+
+    opener: str
+    content: List["ComponentValue"]
+    offset: int
+
+    def __str__(self) -> str:
+        """Serialize the block back to CSS."""
+        return f"{self.opener}{serialize(self.content)}{_CLOSING[self.opener]}"
+
+
+@dataclass
+class Function:
+    """A function such as ``calc(...)`` or ``var(...)``."""
+
+    name: str
+    arguments: List["ComponentValue"]
+    offset: int
+    text: str = ""
+
+    def __str__(self) -> str:
+        """Serialize the function back to CSS."""
+        return f"{self.text or self.name}({serialize(self.arguments)})"
+
+
+ComponentValue = Union[Token, Block, Function]
+
+
+@dataclass
+class Declaration:
+    """A ``name: value`` declaration, with ``!important`` split off."""
+
+    name: str
+    value: List[ComponentValue]
+    offset: int
+    important: bool = False
+
+
+@dataclass
+class QualifiedRule:
+    """A style rule: a prelude (the selector) followed by a block."""
+
+    prelude: List[ComponentValue]
+    content: List["Node"]
+    offset: int
+
+
+@dataclass
+class AtRule:
+    """An at-rule such as ``@media``; ``content`` is None for statements."""
+
+    name: str
+    prelude: List[ComponentValue]
+    content: Optional[List["Node"]]
+    offset: int
+
+
+Node = Union[Declaration, QualifiedRule, AtRule]
+
+
+@dataclass
+class ParseError:
+    """A recoverable syntax error found while tokenizing or parsing."""
+
+    offset: int
+    message: str
+
+
+def serialize(values: List[ComponentValue]) -> str:
+    """Serialize component values, collapsing whitespace to single spaces."""
+    parts = []
+    for value in values:
+        if isinstance(value, Token) and value.type == "whitespace":
+            parts.append(" ")
+        else:
+            parts.append(str(value))
+    return "".join(parts)
+
+
+def tokenize(css: str, errors: Optional[List[ParseError]] = None) -> List[Token]:
+    """Tokenize CSS into a list of tokens, dropping comments."""
+    return _Tokenizer(css, errors).tokenize()
+
+
+def parse_component_values(
+    css: str, errors: Optional[List[ParseError]] = None
+) -> List[ComponentValue]:
+    """Parse CSS into a list of component values."""
+    return _Parser(tokenize(css, errors), errors).consume_component_values()
+
+
+def parse_stylesheet(
+    css: str, errors: Optional[List[ParseError]] = None
+) -> List[Node]:
+    """Parse a stylesheet into its top-level rules."""
+    return _consume_rules(parse_component_values(css, errors), errors)
+
+
+def parse_declarations(
+    css: str, errors: Optional[List[ParseError]] = None
+) -> List[Node]:
+    """Parse the contents of a style block (declarations and nested rules)."""
+    return _consume_block_contents(parse_component_values(css, errors), errors)
+
+
+def _is_space(char: str) -> bool:
+    """Check for a whitespace code point."""
+    return bool(char) and char in _WHITESPACE
+
+
+def _is_digit(char: str) -> bool:
+    """Check for an ASCII digit."""
+    return "0" <= char <= "9" if char else False
+
+
+def _is_name_start(char: str) -> bool:
+    """Check for a code point that may start a name."""
+    if not char:
+        return False
+    return char.isascii() and (char.isalpha() or char == "_") or ord(char) >= 0x80
+
+
+def _is_name(char: str) -> bool:
+    """Check for a code point that may appear in a name."""
+    return _is_name_start(char) or _is_digit(char) or char == "-"
+
+
+def _is_valid_escape(first: str, second: str) -> bool:
+    """Check whether two code points start a valid escape."""
+    return first == "\\" and second not in _NEWLINES
+
+
+def _starts_identifier(first: str, second: str, third: str) -> bool:
+    """Check whether three code points would start an identifier."""
+    if first == "-":
+        return (
+            _is_name_start(second)
+            or second == "-"
+            or _is_valid_escape(second, third)
+        )
+    return _is_name_start(first) or _is_valid_escape(first, second)
+
+
+def _starts_number(first: str, second: str, third: str) -> bool:
+    """Check whether three code points would start a number."""
+    if first and first in "+-":
+        return _is_digit(second) or (second == "." and _is_digit(third))
+    if first == ".":
+        return _is_digit(second)
+    return _is_digit(first)
+
+
+def _is_non_printable(char: str) -> bool:
+    """Check for control characters that are invalid in unquoted urls."""
+    return (ord(char) < 0x20 and not _is_space(char)) or ord(char) == 0x7F
+
+
+class _Tokenizer:
+    """Consumes code points into tokens (CSS Syntax section 4)."""
+
+    def __init__(self, css: str, errors: Optional[List[ParseError]]):
+        """Start tokenizing at the beginning of the source."""
+        self.css = css
+        self.pos = 0
+        self.errors = errors if errors is not None else []
+
+    def peek(self, ahead: int = 0) -> str:
+        """Look at an upcoming code point ("" past the end)."""
+        index = self.pos + ahead
+        return self.css[index] if index < len(self.css) else ""
+
+    def error(self, message: str, offset: Optional[int] = None):
+        """Record a parse error at the current or given offset."""
+        self.errors.append(ParseError(self.pos if offset is None else offset, message))
+
+    def tokenize(self) -> List[Token]:
+        """Consume tokens until the end of the source."""
+        tokens = []
+        while True:
+            self.consume_comments()
+            if self.pos >= len(self.css):
+                return tokens
+            start = self.pos
+            token_type, value, extra = self.consume_token()
+            token = Token(token_type, value, self.css[start : self.pos], start)
+            if token_type == "dimension":
+                token.unit = extra
+            elif token_type == "hash":
+                token.is_id = bool(extra)
+            tokens.append(token)
+
+    def consume_comments(self):
+        """Skip any comments at the current position."""
+        while self.css.startswith("/*", self.pos):
+            end = self.css.find("*/", self.pos + 2)
+            if end == -1:
+                self.error("Unclosed comment")
+                self.pos = len(self.css)
+                return
+            self.pos = end + 2
+
+    def skip_whitespace(self):
+        """Skip whitespace at the current position."""
+        while _is_space(self.peek()):
+            self.pos += 1
+
+    def consume_token(self) -> Tuple[str, Union[str, float, None], object]:
+        """Consume one token as ``(type, value, unit or hash flag)``."""
+        char, second, third = self.peek(), self.peek(1), self.peek(2)
+
+        if char in _WHITESPACE:
+            self.skip_whitespace()
+            return "whitespace", " ", None
+        if char in "\"'":
+            return self.consume_string(char)
+        if char in _PUNCTUATION:
+            self.pos += 1
+            return _PUNCTUATION[char], char, None
+        if char in "#@<":
+            return self.consume_prefixed(char)
+        if _starts_number(char, second, third):
+            return self.consume_numeric()
+        if char == "-" and second == "-" and third == ">":
+            self.pos += 3
+            return "CDC", "-->", None
+        if _starts_identifier(char, second, third):
+            return self.consume_ident_like()
+
+        if char == "\\":
+            self.error("Invalid escape")
+        self.pos += 1
+        return "delim", char, None
+
+    def consume_prefixed(self, char: str) -> Tuple[str, str, object]:
+        """Consume a hash, at-keyword or ``<!--``, or else a delim."""
+        second, third, fourth = self.peek(1), self.peek(2), self.peek(3)
+
+        if char == "#" and (_is_name(second) or _is_valid_escape(second, third)):
+            is_id = _starts_identifier(second, third, fourth)
+            self.pos += 1
+            return "hash", self.consume_name(), is_id
+        if char == "@" and _starts_identifier(second, third, fourth):
+            self.pos += 1
+            return "at-keyword", self.consume_name(), None
+        if self.css.startswith("<!--", self.pos):
+            self.pos += 4
+            return "CDO", "<!--", None
+
+        self.pos += 1
+        return "delim", char, None
+
+    def consume_string(self, quote: str) -> Tuple[str, str, None]:
+        """Consume a quoted string; an unescaped newline makes a bad string."""
+        start = self.pos
+        self.pos += 1
+        value = []
+        while self.peek() != quote:
+            char = self.peek()
+            if not char:
+                self.error("Unclosed string", start)
+                return "string", "".join(value), None
+            if char in _NEWLINES:
+                # The newline is not consumed, it becomes whitespace
+                self.error("Newline in string", start)
+                return "bad-string", "".join(value), None
+            if char == "\\":
+                self.pos += 1
+                value.append(self.consume_string_escape())
+            else:
+                value.append(char)
+                self.pos += 1
+
+        self.pos += 1
+        return "string", "".join(value), None
+
+    def consume_string_escape(self) -> str:
+        """Consume an escape inside a string, where newlines may be escaped."""
+        char = self.peek()
+        if char and char in _NEWLINES:
+            self.pos += 2 if self.css.startswith("\r\n", self.pos) else 1
+            return ""
+        if not char:
+            return ""
+        return self.consume_escape()
+
+    def consume_escape(self) -> str:
+        """Consume an escape (after the backslash) and return its code point."""
+        char = self.peek()
+        if not char:
+            return "\ufffd"
+        if char not in _HEX_DIGITS:
+            self.pos += 1
+            return char
+
+        digits = ""
+        while len(digits) < 6 and self.peek() and self.peek() in _HEX_DIGITS:
+            digits += self.peek()
+            self.pos += 1
+
+        # A single whitespace after a hex escape belongs to the escape
+        if self.css.startswith("\r\n", self.pos):
+            self.pos += 2
+        elif _is_space(self.peek()):
+            self.pos += 1
+
+        code = int(digits, 16)
+        if code == 0 or 0xD800 <= code <= 0xDFFF or code > 0x10FFFF:
+            return "\ufffd"
+        return chr(code)
+
+    def consume_name(self) -> str:
+        """Consume a name made of name code points and escapes."""
+        name = []
+        while True:
+            char = self.peek()
+            if _is_name(char):
+                name.append(char)
+                self.pos += 1
+            elif _is_valid_escape(char, self.peek(1)):
+                self.pos += 1
+                name.append(self.consume_escape())
+            else:
+                return "".join(name)
+
+    def consume_number(self) -> float:
+        """Consume a number, including its sign, fraction and exponent."""
+        start = self.pos
+        if self.peek() and self.peek() in "+-":
+            self.pos += 1
+        self.consume_digits()
+        if self.peek() == "." and _is_digit(self.peek(1)):
+            self.pos += 1
+            self.consume_digits()
+        if self.peek() and self.peek() in "eE":
+            sign = 1 if self.peek(1) and self.peek(1) in "+-" else 0
+            if _is_digit(self.peek(1 + sign)):
+                self.pos += 1 + sign
+                self.consume_digits()
+        return float(self.css[start : self.pos])
+
+    def consume_digits(self):
+        """Skip a run of ASCII digits."""
+        while _is_digit(self.peek()):
+            self.pos += 1
+
+    def consume_numeric(self) -> Tuple[str, float, Optional[str]]:
+        """Consume a number, percentage or dimension token."""
+        number = self.consume_number()
+        if _starts_identifier(self.peek(), self.peek(1), self.peek(2)):
+            return "dimension", number, self.consume_name()
+        if self.peek() == "%":
+            self.pos += 1
+            return "percentage", number, None
+        return "number", number, None
+
+    def consume_ident_like(self) -> Tuple[str, str, None]:
+        """Consume an ident, function or unquoted ``url()`` token."""
+        name = self.consume_name()
+        if self.peek() != "(":
+            return "ident", name, None
+
+        self.pos += 1
+        if name.lower() != "url":
+            return "function", name, None
+
+        # ``url(`` followed by a quoted string is an ordinary function
+        while _is_space(self.peek()) and _is_space(self.peek(1)):
+            self.pos += 1
+        following = self.peek(1) if _is_space(self.peek()) else self.peek()
+        if following and following in "\"'":
+            return "function", name, None
+        return self.consume_url()
+
+    def consume_url(self) -> Tuple[str, str, None]:
+        """Consume an unquoted url, after ``url(``."""
+        start = self.pos
+        value = []
+        self.skip_whitespace()
+
+        while self.peek() and self.peek() != ")":
+            char = self.peek()
+            if char in _WHITESPACE:
+                self.skip_whitespace()
+                if self.peek() and self.peek() != ")":
+                    return self.consume_bad_url(start)
+            elif char in "\"'(" or _is_non_printable(char):
+                return self.consume_bad_url(start)
+            elif char == "\\":
+                if not _is_valid_escape(char, self.peek(1)):
+                    return self.consume_bad_url(start)
+                self.pos += 1
+                value.append(self.consume_escape())
+            else:
+                value.append(char)
+                self.pos += 1
+
+        if self.peek():
+            self.pos += 1
+        else:
+            self.error("Unclosed url()", start)
+        return "url", "".join(value), None
+
+    def consume_bad_url(self, start: int) -> Tuple[str, str, None]:
+        """Consume the rest of an invalid url up to its closing paren."""
+        self.error("Invalid url()", start)
+        while self.peek() and self.peek() != ")":
+            if _is_valid_escape(self.peek(), self.peek(1)):
+                self.pos += 1
+            self.pos += 1
+        if self.peek():
+            self.pos += 1
+        return "bad-url", self.css[start : self.pos], None
+
+
+class _Parser:
+    """Consumes tokens into component values (CSS Syntax section 5)."""
+
+    def __init__(self, tokens: List[Token], errors: Optional[List[ParseError]]):
+        """Start parsing at the first token."""
+        self.tokens = tokens
+        self.pos = 0
+        self.errors = errors if errors is not None else []
+
+    def consume_component_values(
+        self, closing: Optional[str] = None
+    ) -> List[ComponentValue]:
+        """Consume component values up to ``closing`` (or the end)."""
+        values: List[ComponentValue] = []
+        while self.pos < len(self.tokens):
+            token = self.tokens[self.pos]
+            self.pos += 1
+            if closing and token.type == closing:
+                return values
+            values.append(self.consume_component_value(token))
+
+        if closing:
+            offset = self.tokens[-1].offset if self.tokens else 0
+            self.errors.append(ParseError(offset, f"Missing closing '{closing}'"))
+        return values
+
+    def consume_component_value(self, token: Token) -> ComponentValue:
+        """Consume a token, a simple block or a function."""
+        if token.type in _CLOSING:
+            content = self.consume_component_values(_CLOSING[token.type])
+            return Block(token.type, content, token.offset)
+        if token.type == "function":
+            arguments = self.consume_component_values(")")
+            return Function(str(token.value), arguments, token.offset, token.text[:-1])
+        return token
+
+
+def _is_token(value: ComponentValue, *types: str) -> bool:
+    """Check whether a component value is a token of one of the types."""
+    return isinstance(value, Token) and value.type in types
+
+
+def _is_curly_block(value: ComponentValue) -> bool:
+    """Check whether a component value is a ``{}`` block."""
+    return isinstance(value, Block) and value.opener == "{"
+
+
+def _strip(values: List[ComponentValue]) -> List[ComponentValue]:
+    """Remove leading and trailing whitespace tokens."""
+    start, end = 0, len(values)
+    while start < end and _is_token(values[start], "whitespace"):
+        start += 1
+    while end > start and _is_token(values[end - 1], "whitespace"):
+        end -= 1
+    return values[start:end]
+
+
+def _consume_rules(
+    values: List[ComponentValue], errors: Optional[List[ParseError]]
+) -> List[Node]:
+    """Consume the top-level rules of a stylesheet."""
+    rules: List[Node] = []
+    pos = 0
+    while pos < len(values):
+        value = values[pos]
+        if _is_token(value, "whitespace", "CDO", "CDC"):
+            pos += 1
+        elif _is_token(value, "at-keyword"):
+            rule, pos = _consume_at_rule(values, pos, errors)
+            rules.append(rule)
+        else:
+            end = pos
+            while end < len(values) and not _is_curly_block(values[end]):
+                end += 1
+            if end == len(values):
+                _report(errors, value.offset, "Style rule without a block")
+                return rules
+            rules.append(_qualified_rule(values[pos:end], values[end], errors))
+            pos = end + 1
+
+    return rules
+
+
+def _consume_at_rule(
+    values: List[ComponentValue], pos: int, errors: Optional[List[ParseError]]
+) -> Tuple[AtRule, int]:
+    """Consume an at-rule ending in ``;`` or a ``{}`` block."""
+    keyword = values[pos]
+    end = pos + 1
+    while end < len(values) and not _is_token(values[end], "semicolon"):
+        if _is_curly_block(values[end]):
+            break
+        end += 1
+
+    content = None
+    if end < len(values) and _is_curly_block(values[end]):
+        content = _consume_block_contents(values[end].content, errors)
+
+    prelude = _strip(values[pos + 1 : end])
+    return AtRule(str(keyword.value), prelude, content, keyword.offset), end + 1
+
+
+def _consume_block_contents(
+    values: List[ComponentValue], errors: Optional[List[ParseError]]
+) -> List[Node]:
+    """Consume the declarations, at-rules and nested style rules of a block."""
+    nodes: List[Node] = []
+    pos = 0
+    while pos < len(values):
+        value = values[pos]
+        if _is_token(value, "whitespace", "semicolon"):
+            pos += 1
+            continue
+        if _is_token(value, "at-keyword"):
+            rule, pos = _consume_at_rule(values, pos, errors)
+            nodes.append(rule)
+            continue
+
+        # A ``{}`` block before the next ``;`` makes this a nested style rule,
+        # except in custom properties, whose values may contain blocks
+        custom = _is_custom_property(values[pos:])
+        end = pos
+        while end < len(values) and not _is_token(values[end], "semicolon"):
+            if _is_curly_block(values[end]) and not custom:
+                break
+            end += 1
+
+        if end < len(values) and _is_curly_block(values[end]):
+            nodes.append(_qualified_rule(values[pos:end], values[end], errors))
+        else:
+            declaration = _consume_declaration(values[pos:end])
+            if declaration:
+                nodes.append(declaration)
+            else:
+                _report(errors, value.offset, "Invalid declaration")
+        pos = end + 1
+
+    return nodes
+
+
+def _qualified_rule(
+    prelude: List[ComponentValue], block: Block, errors: Optional[List[ParseError]]
+) -> QualifiedRule:
+    """Create a style rule from its prelude and ``{}`` block."""
+    prelude = _strip(prelude)
+    return QualifiedRule(
+        prelude=prelude,
+        content=_consume_block_contents(block.content, errors),
+        offset=prelude[0].offset if prelude else block.offset,
+    )
+
+
+def _is_custom_property(values: List[ComponentValue]) -> bool:
+    """Check whether values start with a ``--name:`` custom property."""
+    values = [value for value in values[:4] if not _is_token(value, "whitespace")]
+    return (
+        len(values) >= 2
+        and _is_token(values[0], "ident")
+        and str(values[0].value).startswith("--")
+        and _is_token(values[1], "colon")
+    )
+
+
+def _consume_declaration(values: List[ComponentValue]) -> Optional[Declaration]:
+    """Consume ``name: value [!important]``, or None if it is not one."""
+    values = _strip(values)
+    if len(values) < 2 or not _is_token(values[0], "ident"):
+        return None
+
+    name = values[0]
+    rest = _strip(values[1:])
+    if not rest or not _is_token(rest[0], "colon"):
+        return None
+
+    value = _strip(rest[1:])
+    important = _important_index(value)
+    if important is not None:
+        value = _strip(value[:important])
+
+    return Declaration(name.text, value, name.offset, important is not None)
+
+
+def _important_index(value: List[ComponentValue]) -> Optional[int]:
+    """Find the ``!`` of a trailing ``!important``, if the value has one."""
+    significant = [
+        index for index, item in enumerate(value) if not _is_token(item, "whitespace")
+    ]
+    if len(significant) < 2:
+        return None
+
+    bang, keyword = value[significant[-2]], value[significant[-1]]
+    if (
+        _is_token(bang, "delim")
+        and bang.value == "!"
+        and _is_token(keyword, "ident")
+        and str(keyword.value).lower() == "important"
+    ):
+        return significant[-2]
+    return None
+
+
+def _report(errors: Optional[List[ParseError]], offset: int, message: str):
+    """Record a parse error if the caller collects them."""
+    if errors is not None:
+        errors.append(ParseError(offset, message))
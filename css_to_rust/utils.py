@@ -1,7 +1,10 @@
 """Utility functions for CSS to Rust conversion."""
 
+import hashlib
 import re
-from typing import Any, Dict, List, Optional
+from typing import Any, Dict, List, Optional, Tuple
+
+_USE_RE = re.compile(r"^use\s+([\w:]+)::(?:\{([^}]*)\}|([\w*]+));$")
 
 
 def normalize_selector(selector: str) -> str:
@@ -118,6 +121,59 @@ def sanitize_rust_identifier(name: str) -> str:
     return sanitized
 
 
+def scoped_class_name(name: str, css_content: str) -> str:
+    """Derive a class name unique to some CSS, such as ``button-1a2b3c4d``."""
+    digest = hashlib.sha256(css_content.encode("utf-8")).hexdigest()[:8]
+    return f"{name.replace('_', '-')}-{digest}"
+
+
+def module_class_name(name: str, stylesheet: str) -> str:
+    """Derive a stylesheet's hashed name for a class, such as ``button_a1b2c3``."""
+    digest = hashlib.sha256(f"{stylesheet}:{name}".encode("utf-8")).hexdigest()[:6]
+    return f"{name}_{digest}"
+
+
+def split_rust_items(code: str) -> Tuple[List[str], str]:
+    """Split generated Rust code into its ``use`` lines and its items.
+
+    Module doc comments and blank lines ahead of the first item are dropped,
+    so several generated snippets can share one file header.
+    """
+    lines = code.split("\n")
+    imports = []
+    for i, line in enumerate(lines):
+        if line.startswith("use "):
+            imports.append(line)
+        elif line.strip() and not line.startswith("//!"):
+            return imports, "\n".join(lines[i:])
+    return imports, ""
+
+
+def merge_use_statements(statements: List[str]) -> List[str]:
+    """Merge ``use`` statements importing from the same path.
+
+    ``use stylist::Style;`` and ``use stylist::{css, StyleSource};`` become
+    ``use stylist::{css, Style, StyleSource};``, with names ordered the way
+    rustfmt orders them.
+    """
+    paths: Dict[str, List[str]] = {}
+    for statement in statements:
+        match = _USE_RE.match(statement.strip())
+        if not match:
+            continue
+        names = paths.setdefault(match.group(1), [])
+        for name in (match.group(2) or match.group(3)).split(","):
+            if name.strip() and name.strip() not in names:
+                names.append(name.strip())
+
+    merged = []
+    for path, names in sorted(paths.items()):
+        names.sort(key=lambda name: (name[0].isupper(), name))
+        imported = names[0] if len(names) == 1 else "{" + ", ".join(names) + "}"
+        merged.append(f"use {path}::{imported};")
+    return merged
+
+
 def format_css_property(property_name: str, property_value: str) -> str:
     """Format a CSS property for inclusion in Rust code."""
     # Normalize property name
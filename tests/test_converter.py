@@ -1,5 +1,6 @@
 """Tests for CSS to Rust converter module."""
 
+import pytest
 
 from css_to_rust.converter import CssToRustConverter
 
@@ -123,3 +124,387 @@ class TestCssToRustConverter:
         except Exception as e:
             # Should raise a meaningful exception
             assert str(e) != ""
+
+    def test_selector_list_functions(self):
+        """Test that scoped selector lists produce a function per selector."""
+        css = """
+        h1, h2, h3 { margin: 0; }
+        .btn-primary, .btn-secondary { border: none; }
+        """
+
+        functions = self.converter.convert_string(css)
+
+        assert set(functions) == {"global_styles", "btn_primary", "btn_secondary"}
+        assert all(name.isidentifier() for name in functions)
+        assert "h1, h2, h3 {" in functions["global_styles"]
+        assert "border: none;" in functions["btn_secondary"]
+
+    def test_convert_scss_file(self, tmp_path):
+        """Test converting an SCSS file with variables and nesting."""
+        scss_file = tmp_path / "button.scss"
+        scss_file.write_text(
+            """
+            $brand: #3366ff;
+            .button {
+                color: $brand;
+                &:hover { opacity: 0.9; }
+                &-large { font-size: 18px; }
+            }
+            """
+        )
+        output_file = tmp_path / "button.rs"
+
+        result = self.converter.convert_file(
+            str(scss_file), str(output_file), scss_variables=True
+        )
+
+        assert result["mapping_candidates"] == {"#3366ff": "var(--brand)"}
+        assert set(result["functions"]) == {"button", "button_large"}
+        rust_code = output_file.read_text()
+        assert "color: var(--brand);" in rust_code
+        assert "&:hover {" in rust_code
+
+    def test_validate_css_diagnostics(self):
+        """Test validate_css returns diagnostics pointing at declarations."""
+        css = """.box {
+  width: calc(100% - 8px);
+}
+"""
+        diagnostics = self.converter.validate_css(css, "box.css")
+        calc = [d for d in diagnostics if d.code == "calc-function"]
+
+        assert len(calc) == 1
+        assert str(calc[0].span) == "box.css:2:3"
+
+    def test_analyze_css_layers(self):
+        """Test analyze_css reports rules per cascade layer in declared order."""
+        css = """
+        @layer base, components, utilities;
+        @layer components {
+            .button { padding: 8px; }
+            .card { margin: 0; }
+        }
+        @layer base { body { margin: 0; } }
+        .legacy { float: left; }
+        """
+        stats = self.converter.analyze_css(css)
+
+        assert stats["layers"] == {
+            "base": 1,
+            "components": 2,
+            "utilities": 0,
+            "(unlayered)": 1,
+        }
+
+    def test_global_styles_module(self, tmp_path):
+        """Test global selectors are written to their own GlobalStyle module."""
+        css_file = tmp_path / "app.css"
+        css_file.write_text(
+            """
+            :root { --brand: #3366ff; color-scheme: light; }
+            * { box-sizing: border-box; }
+            body { margin: 0; }
+            a:visited { color: purple; }
+            .card a { color: inherit; }
+            """
+        )
+
+        result = self.converter.convert_file(
+            str(css_file), str(tmp_path / "styles"), group_by_component=True
+        )
+
+        assert result["global_styles"]
+        assert "global" in result["components"]
+        global_rs = (tmp_path / "styles" / "global.rs").read_text()
+        assert "pub fn global_styles() -> GlobalStyle {" in global_rs
+        assert "<Global css={GLOBAL_CSS} />" in global_rs
+        for selector in (":root {", "* {", "body {", "a:visited {"):
+            assert f"\n    {selector}" in global_rs
+        assert "pub mod global;" in (tmp_path / "styles" / "mod.rs").read_text()
+        card_rs = (tmp_path / "styles" / "card.rs").read_text()
+        assert "& a {" in card_rs
+
+    def test_global_styles_inline_module(self, tmp_path):
+        """Test single-file output keeps global styles in an inline module."""
+        css_file = tmp_path / "app.css"
+        css_file.write_text("html { font-size: 16px; } .button { color: red; }")
+        output_file = tmp_path / "app.rs"
+
+        self.converter.convert_file(str(css_file), str(output_file))
+
+        rust_code = output_file.read_text()
+        assert "pub fn button() -> Style {" in rust_code
+        assert "pub mod global {" in rust_code
+        assert "    pub fn global_styles() -> GlobalStyle {" in rust_code
+
+    def test_font_faces_copied(self, tmp_path):
+        """Test @font-face goes to global styles with fonts copied alongside."""
+        (tmp_path / "assets").mkdir()
+        (tmp_path / "assets" / "inter.woff2").write_bytes(b"wOF2")
+        (tmp_path / "fonts.css").write_text(
+            """
+            @font-face {
+                font-family: "Inter";
+                font-weight: 400;
+                src: url("assets/inter.woff2") format("woff2"),
+                     url(https://example.com/inter.woff) format("woff"),
+                     url(assets/missing.ttf);
+            }
+            """
+        )
+        (tmp_path / "app.css").write_text('@import "fonts.css"; .app { color: red; }')
+        output_dir = tmp_path / "out"
+
+        result = self.converter.convert_file(
+            str(tmp_path / "app.css"),
+            str(output_dir),
+            group_by_component=True,
+            copy_fonts=True,
+        )
+
+        assert (output_dir / "fonts" / "inter.woff2").read_bytes() == b"wOF2"
+        global_rs = (output_dir / "global.rs").read_text()
+        assert 'url("fonts/inter.woff2") format("woff2")' in global_rs
+        assert 'url("https://example.com/inter.woff")' in global_rs
+        assert "font-weight: 400;" in global_rs
+        assert [d.code for d in result["diagnostics"] if d.code.startswith("font")] == [
+            "font-not-found"
+        ]
+
+    def test_convert_string_font_faces(self):
+        """Test convert_string keeps @font-face rules in global styles."""
+        functions = self.converter.convert_string(
+            '@font-face { font-family: "Inter"; src: url(inter.woff2); }'
+        )
+
+        assert "@font-face" in functions["global_styles"]
+        assert 'font-family: "Inter";' in functions["global_styles"]
+
+    def test_theme_module(self, tmp_path):
+        """Test custom properties become a typed theme module."""
+        css_file = tmp_path / "theme.css"
+        css_file.write_text(
+            """
+            :root { --color-primary: #007bff; --spacing: 16px; }
+            [data-theme="dark"] { --color-primary: #66b2ff; }
+            .card { --card-pad: 4px; padding: var(--spacing); }
+            """
+        )
+
+        result = self.converter.convert_file(
+            str(css_file), str(tmp_path / "styles"), group_by_component=True
+        )
+
+        assert result["modules"] == ["theme"]
+        theme_rs = (tmp_path / "styles" / "theme.rs").read_text()
+        assert 'pub const COLOR_PRIMARY: &str = "var(--color-primary)";' in theme_rs
+        assert "pub const DARK: Theme = Theme {" in theme_rs
+        assert '        color_primary: "#66b2ff",\n        spacing: "16px",' in theme_rs
+        assert "pub fn theme_style(theme: &Theme) -> GlobalStyle {" in theme_rs
+        card_rs = (tmp_path / "styles" / "card.rs").read_text()
+        assert "--card-pad:" in card_rs
+
+    def test_variant_enums(self, tmp_path):
+        """Test variant enum mode writes one function per component."""
+        css_file = tmp_path / "button.css"
+        css_file.write_text(
+            """
+            .btn { padding: 8px; }
+            .btn-primary { color: white; }
+            .btn-lg { font-size: 18px; }
+            .card { margin: 0; }
+            """
+        )
+        output_file = tmp_path / "button.rs"
+
+        result = self.converter.convert_file(
+            str(css_file), str(output_file), variant_enums=True
+        )
+
+        assert result["functions"] == ["button", "card"]
+        rust_code = output_file.read_text()
+        assert "pub enum ButtonVariant {" in rust_code
+        assert "pub enum ButtonSize {" in rust_code
+        assert rust_code.count("use stylist::Style;") == 1
+        assert "pub fn card() -> Style {" in rust_code
+
+    def test_variant_enum_params(self, tmp_path):
+        """Test parameters variant functions can't take are reported."""
+        css_file = tmp_path / "button.css"
+        css_file.write_text(
+            ".btn { padding: 8px; }\n"
+            ".btn-primary { color: white; /* @param color: &str */ }\n"
+        )
+        output_file = tmp_path / "button.rs"
+
+        result = self.converter.convert_file(
+            str(css_file), str(output_file), variant_enums=True
+        )
+
+        [diagnostic] = [d for d in result["diagnostics"] if d.code == "variant-param"]
+        assert diagnostic.severity == "warning"
+        assert "color of .btn-primary" in diagnostic.message
+        assert diagnostic.span.line == 2
+        assert "ButtonVariant::Primary => r#\"" in output_file.read_text()
+
+        self.converter.convert_string(css_file.read_text(), variant_enums=True)
+        codes = [d.code for d in self.converter.parser.diagnostics]
+        assert "variant-param" in codes
+
+    def test_style_macro_header(self, tmp_path):
+        """Test the file header imports what macro and variant functions use."""
+        css_file = tmp_path / "button.css"
+        css_file.write_text(".btn { padding: 8px; } .btn-sm { font-size: 12px; }")
+        output_file = tmp_path / "button.rs"
+
+        self.converter.convert_file(
+            str(css_file),
+            str(output_file),
+            style_macro="css",
+            variant_enums=True,
+            include_utilities=True,
+        )
+
+        rust_code = output_file.read_text()
+        assert rust_code.count("use stylist::") == 1
+        assert "use stylist::{css, Style, StyleSource};" in rust_code
+        assert "pub fn flex_center() -> StyleSource {" in rust_code
+        assert "Style::new([base_css, size_css].concat())" in rust_code
+
+    def test_style_errors_option(self, tmp_path):
+        """Test the Result option reaches keyframe functions."""
+        css_file = tmp_path / "fade.css"
+        css_file.write_text("@keyframes fade { to { opacity: 1; } }")
+        output_file = tmp_path / "fade.rs"
+
+        self.converter.convert_file(
+            str(css_file), str(output_file), style_errors="result"
+        )
+
+        rust_code = output_file.read_text()
+        assert "pub fn animation_fade() -> Result<Style, stylist::Error> {" in (
+            rust_code
+        )
+        assert "expect" not in rust_code
+        assert self.converter.get_conversion_options()["style_errors"]["choices"] == [
+            "panic",
+            "result",
+            "both",
+        ]
+
+    def test_leptos_target(self, tmp_path):
+        """Test the Leptos target mounts global and theme styles with <Style>."""
+        css_file = tmp_path / "app.css"
+        css_file.write_text(
+            """
+            :root { --brand: #3366ff; }
+            body { margin: 0; }
+            .card { float: left; }
+            @keyframes fade { to { opacity: 1; } }
+            """
+        )
+
+        result = self.converter.convert_file(
+            str(css_file),
+            str(tmp_path / "styles"),
+            group_by_component=True,
+            target="leptos",
+        )
+
+        assert result["global_styles"]
+        card_rs = (tmp_path / "styles" / "card.rs").read_text()
+        assert "pub fn CardStyle() -> impl IntoView {" in card_rs
+        animations_rs = (tmp_path / "styles" / "animations.rs").read_text()
+        assert "        @keyframes fade {" in animations_rs
+        assert 'view! { <Style id="animation-fade">' in animations_rs
+        global_rs = (tmp_path / "styles" / "global.rs").read_text()
+        assert "pub fn GlobalStyles() -> impl IntoView {" in global_rs
+        theme_rs = (tmp_path / "styles" / "theme.rs").read_text()
+        assert "pub fn ThemeStyle(theme: Theme) -> impl IntoView {" in theme_rs
+        assert "stylist" not in global_rs + theme_rs + card_rs
+
+    def test_unsupported_target_options(self, tmp_path):
+        """Test stylist and Yew options are rejected for other targets."""
+        css_file = tmp_path / "card.css"
+        css_file.write_text(".card { float: left; }")
+        options = {"target": "dioxus", "emit_components": True, "cache_styles": True}
+
+        assert self.converter.unsupported_options(**options) == [
+            "emit_components",
+            "cache_styles",
+        ]
+        assert self.converter.unsupported_options(target="dioxus") == []
+        with pytest.raises(ValueError, match="can't be used with the dioxus target"):
+            self.converter.convert_file(
+                str(css_file), str(tmp_path / "card.rs"), **options
+            )
+
+    def test_emit_components(self, tmp_path):
+        """Test a component follows each style function in its module."""
+        css_file = tmp_path / "app.css"
+        css_file.write_text(
+            """
+            .btn { padding: 8px; }
+            .btn-primary { color: white; }
+            .btn-sm { font-size: 12px; }
+            .card { margin: 0; }
+            """
+        )
+
+        self.converter.convert_file(
+            str(css_file),
+            str(tmp_path / "styles"),
+            group_by_component=True,
+            emit_components=True,
+        )
+
+        button_rs = (tmp_path / "styles" / "button.rs").read_text()
+        assert "use stylist::Style;\nuse yew::prelude::*;\n" in button_rs
+        assert "pub fn button(variant: ButtonVariant, size: ButtonSize)" in button_rs
+        assert "pub fn button_component(props: &ButtonProps) -> Html {" in button_rs
+        card_rs = (tmp_path / "styles" / "card.rs").read_text()
+        assert "#[function_component(Card)]" in card_rs
+        assert "let style = card();" in card_rs
+
+    def test_css_modules(self, tmp_path):
+        """Test a stylesheet is written next to its class name constants."""
+        css_file = tmp_path / "button.css"
+        css_file.write_text(
+            ".button { color: #007bff; } @keyframes spin { to { opacity: 0; } }"
+        )
+        output_file = tmp_path / "out" / "button.rs"
+
+        result = self.converter.convert_file(
+            str(css_file), str(output_file), css_modules=True
+        )
+
+        hashed_name = result["classes"]["button"]
+        assert hashed_name.startswith("button_")
+        stylesheet = (tmp_path / "out" / "button.module.css").read_text()
+        assert f".{hashed_name} {{" in stylesheet
+        assert "@keyframes spin {" in stylesheet
+        code = output_file.read_text()
+        assert 'include_str!("button.module.css")' in code
+        assert f'pub const BUTTON: &str = "{hashed_name}";' in code
+        assert "stylist" not in code
+
+    def test_css_modules_in_place(self, tmp_path):
+        """Test converting next to the source leaves the source unchanged."""
+        source = ".button { color: #007bff; }"
+        css_file = tmp_path / "button.css"
+        css_file.write_text(source)
+
+        result = self.converter.convert_file(
+            str(css_file), str(css_file.with_suffix(".rs")), css_modules=True
+        )
+
+        assert css_file.read_text() == source
+        assert result["stylesheet"] == str(tmp_path / "button.module.css")
+
+        module_file = tmp_path / "app.module.css"
+        module_file.write_text(source)
+        with pytest.raises(ValueError, match="would overwrite the input file"):
+            self.converter.convert_file(
+                str(module_file), str(tmp_path / "app.module"), css_modules=True
+            )
+        assert module_file.read_text() == source
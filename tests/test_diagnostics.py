@@ -0,0 +1,57 @@
+"""Tests for diagnostics module."""
+
+
+from css_to_rust.diagnostics import Diagnostic, SourceIndex, SourceSpan
+
+
+class TestSourceIndex:
+    """Test SourceIndex class."""
+
+    def test_position_and_span(self):
+        """Test offsets are mapped to 1-based lines and columns."""
+        index = SourceIndex(".a {\n  color: red;\n}\n", "a.css")
+
+        assert index.position(0) == (1, 1)
+        assert index.position(7) == (2, 3)
+
+        span = index.span(7, 12)
+        assert span == SourceSpan(2, 3, 2, 8, "a.css")
+        assert str(span) == "a.css:2:3"
+
+
+class TestDiagnostic:
+    """Test Diagnostic class."""
+
+    def test_str(self):
+        """Test the one-line form."""
+        diagnostic = Diagnostic("warning", "empty-rule", "Empty rule: .a")
+        assert str(diagnostic) == "warning[empty-rule]: Empty rule: .a"
+
+        diagnostic.span = SourceSpan(3, 1, file="a.css")
+        assert str(diagnostic) == "warning[empty-rule]: Empty rule: .a at a.css:3:1"
+
+    def test_render(self):
+        """Test the rustc-style snippet points at the span."""
+        source = ".a {\n  color: ;\n}\n"
+        diagnostic = Diagnostic(
+            "warning",
+            "empty-declaration",
+            "Declaration without a value skipped: color:",
+            SourceSpan(2, 3, 2, 9, "a.css"),
+        )
+
+        assert diagnostic.render(source) == "\n".join(
+            [
+                "warning[empty-declaration]: "
+                "Declaration without a value skipped: color:",
+                " --> a.css:2:3",
+                "  |",
+                "2 |   color: ;",
+                "  |   ^^^^^^",
+            ]
+        )
+
+    def test_render_without_source(self):
+        """Test rendering falls back to the location when source is missing."""
+        diagnostic = Diagnostic("error", "parse-error", "Bad", SourceSpan(1, 1))
+        assert diagnostic.render() == "error[parse-error]: Bad\n --> <input>:1:1"
@@ -111,3 +111,50 @@ class TestValueMappings:
 
         # Unknown property
         assert self.mappings._get_category_for_property("unknown-property") is None
+
+    def test_condition_mapping(self):
+        """Test breakpoints resolve to literal lengths inside query conditions."""
+        assert (
+            self.mappings.map_condition("card (min-width: 768px)")
+            == "card (min-width: 768px)"
+        )
+        condition = "(inline-size >= var(--breakpoint-xl)) and (hover)"
+        assert (
+            self.mappings.map_condition(condition)
+            == "(inline-size >= 1200px) and (hover)"
+        )
+        assert (
+            self.mappings.map_condition("(min-width: var(--gutter))")
+            == "(min-width: var(--gutter))"
+        )
+
+        self.mappings.mappings["breakpoints"]["768px"] = "48em"
+        assert self.mappings.map_condition("(width > 768px)") == "(width > 48em)"
+
+    def test_variable_candidates(self):
+        """Test that variables are offered as opt-in mapping candidates."""
+        self.mappings.add_candidates({"brand": "#3366ff", "gutter": "25px"})
+
+        assert self.mappings.get_candidates() == {
+            "#3366ff": "var(--brand)",
+            "25px": "var(--gutter)",
+        }
+        assert self.mappings.map_value("color", "#3366ff") == "#3366ff"
+
+        self.mappings.use_candidates = True
+        assert self.mappings.map_value("color", "#3366ff") == "var(--brand)"
+        assert self.mappings.map_value("padding", "25px") == "var(--gutter)"
+        # Configured mappings still take precedence
+        assert self.mappings.map_value("padding", "8px") == "var(--spacing-sm)"
+
+
+    def test_parameters_from_config(self, tmp_path):
+        """Test parameter properties are read from the config, not mapped."""
+        config = tmp_path / "mappings.json"
+        config.write_text('{"parameters": {"width": "u32"}, "colors": {"red": "x"}}')
+
+        mappings = ValueMappings(str(config))
+
+        assert mappings.parameters == {"width": "u32"}
+        assert "parameters" not in mappings.get_mappings()
+        assert mappings.map_value("color", "red") == "x"
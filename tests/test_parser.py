@@ -1,7 +1,16 @@
 """Tests for CSS parser module."""
 
 
-from css_to_rust.parser import CssKeyframe, CssParser, CssRule
+from css_to_rust.parser import (
+    CssDeclaration,
+    CssKeyframe,
+    CssParam,
+    CssParser,
+    CssRule,
+    ScssParser,
+    parse_selector,
+    split_selector_list,
+)
 
 
 class TestCssParser:
@@ -126,6 +135,214 @@ class TestCssParser:
                 "focus",
             ]
 
+    def test_split_pseudo_selector(self):
+        """Test splitting pseudo-classes and pseudo-elements off selectors."""
+        split = self.parser._split_pseudo_selector
+
+        assert split(".button:hover") == (".button", "hover")
+        assert split(".button::before") == (".button", ":before")
+        assert split(".button:not(.disabled):focus-visible") == (
+            ".button",
+            "not(.disabled):focus-visible",
+        )
+        assert split("li:nth-child(2n+1)") == ("li", "nth-child(2n+1)")
+        assert split('a[href^="http:"]') == ('a[href^="http:"]', None)
+        assert split(":root") == (":root", None)
+        assert split(".button") == (".button", None)
+
+    def test_parse_pseudo_elements(self):
+        """Test parsing pseudo-elements and functional pseudo-classes."""
+        css = """
+        .button::before { content: ""; }
+        .button:not(.disabled):focus-visible { outline: none; }
+        """
+        rules = self.parser.parse(css)
+
+        assert len(rules) == 2
+        assert rules[0].selector == ".button"
+        assert rules[0].pseudo_selector == ":before"
+        assert rules[1].selector == ".button"
+        assert rules[1].pseudo_selector == "not(.disabled):focus-visible"
+
+    def test_parse_combinator_selectors(self):
+        """Test that combinator selectors are owned by their first compound."""
+        css = """
+        .card .title { font-weight: bold; }
+        .card > .body { padding: 8px; }
+        .card:hover .icon { opacity: 1; }
+        .btn[disabled] { cursor: default; }
+        """
+        rules = self.parser.parse(css)
+
+        assert [rule.selector for rule in rules] == [".card", ".card", ".card", ".btn"]
+        assert [rule.parsed_selector.nested_css() for rule in rules] == [
+            "& .title",
+            "& > .body",
+            "&:hover .icon",
+            "&[disabled]",
+        ]
+        groups = self.parser.group_rules_by_component(rules)
+        assert len(groups["card"]) == 3
+
+    def test_parse_selector_list(self):
+        """Test that selector lists expand into one rule per selector."""
+        css = """
+        h1, h2, h3 { margin: 0; }
+        .btn-primary, .btn-primary:hover { color: white; }
+        """
+        rules = self.parser.parse(css)
+
+        assert [rule.selector for rule in rules] == [
+            "h1",
+            "h2",
+            "h3",
+            ".btn-primary",
+            ".btn-primary",
+        ]
+        assert all(rule.properties == {"margin": "0"} for rule in rules[:3])
+        assert rules[0].selector_group == "h1, h2, h3"
+        assert rules[4].pseudo_selector == "hover"
+        assert rules[4].selector_group == ".btn-primary, .btn-primary:hover"
+
+        # Rules from a list do not share a mutable properties dict
+        rules[0].properties["padding"] = "0"
+        assert "padding" not in rules[1].properties
+
+    def test_parse_native_nesting(self):
+        """Test flattening native CSS nesting into owned rules."""
+        css = """
+        .card {
+            padding: 16px;
+            & .title { font-weight: bold; }
+            &:hover { box-shadow: none; }
+            > .body { margin: 0; }
+            .icon { width: 16px; }
+            &.active {
+                & .title { color: red; }
+            }
+        }
+        """
+        rules = self.parser.parse(css)
+
+        assert all(rule.selector == ".card" for rule in rules)
+        assert [rule.parsed_selector.to_css() for rule in rules] == [
+            ".card",
+            ".card .title",
+            ".card:hover",
+            ".card > .body",
+            ".card .icon",
+            ".card.active .title",
+        ]
+        assert rules[0].properties == {"padding": "16px"}
+        assert rules[2].pseudo_selector == "hover"
+
+    def test_parse_nested_media_and_lists(self):
+        """Test nested media queries and selector lists inside nesting."""
+        css = """
+        .btn-primary, .btn-secondary {
+            border: none;
+            &:hover, &:focus { opacity: 0.9; }
+            @media (max-width: 600px) {
+                width: 100%;
+                @media (hover: none) { opacity: 1; }
+            }
+        }
+        @keyframes spin { from { opacity: 0; } to { opacity: 1; } }
+        """
+        rules = self.parser.parse(css)
+
+        hover_rules = [r for r in rules if r.selector_group and "hover" in r.raw_css]
+        assert [r.parsed_selector.to_css() for r in hover_rules] == [
+            ".btn-primary:hover",
+            ".btn-primary:focus",
+            ".btn-secondary:hover",
+            ".btn-secondary:focus",
+        ]
+        media = [r.media_query for r in rules if r.media_query]
+        assert media == [
+            "(max-width: 600px)",
+            "(max-width: 600px)",
+            "(max-width: 600px) and (hover: none)",
+            "(max-width: 600px) and (hover: none)",
+        ]
+        assert self.parser.keyframes[0].name == "spin"
+
+    def test_parse_supports(self):
+        """Test @supports conditions are recorded alongside media queries."""
+        css = """
+        @supports (display: grid) {
+            .grid { display: grid; }
+            @media (min-width: 600px) {
+                .grid { gap: 16px; }
+            }
+        }
+        .card {
+            float: left;
+            @supports (display: flex) {
+                display: flex;
+                @supports (gap: 1px) { gap: 8px; }
+            }
+        }
+        """
+        rules = self.parser.parse(css)
+
+        assert [
+            (r.selector, r.media_query, r.supports_condition) for r in rules
+        ] == [
+            (".grid", None, "(display: grid)"),
+            (".grid", "(min-width: 600px)", "(display: grid)"),
+            (".card", None, None),
+            (".card", None, "(display: flex)"),
+            (".card", None, "(display: flex) and (gap: 1px)"),
+        ]
+
+    def test_parse_container_queries(self):
+        """Test @container conditions, including nested and named ones."""
+        css = """
+        .card { container: card / inline-size; }
+        @container card (min-width: 400px) {
+            .title { font-size: 2rem; }
+            @container (max-width: 800px) {
+                .title { margin: 0; }
+            }
+            @container sidebar (min-width: 200px) {
+                .title { display: none; }
+            }
+        }
+        """
+        rules = self.parser.parse(css)
+
+        assert [r.container_query for r in rules] == [
+            None,
+            "card (min-width: 400px)",
+            "card (min-width: 400px) and (max-width: 800px)",
+            "sidebar (min-width: 200px)",
+        ]
+        assert [d.code for d in self.parser.diagnostics] == ["nested-container"]
+
+    def test_parse_layers(self):
+        """Test layer membership and declaration order are recorded."""
+        css = """
+        @layer reset, components;
+        @layer components {
+            .button { padding: 8px; }
+            @layer variants {
+                .button-primary { color: blue; }
+            }
+        }
+        @layer { .anonymous { color: red; } }
+        .legacy { float: left; }
+        """
+        rules = self.parser.parse(css)
+
+        assert [(r.selector, r.layer) for r in rules] == [
+            (".button", "components"),
+            (".button-primary", "components.variants"),
+            (".anonymous", ""),
+            (".legacy", None),
+        ]
+        assert self.parser.layers == ["reset", "components", "components.variants"]
+
     def test_parse_comments(self):
         """Test parsing with comments."""
         css = """
@@ -143,6 +360,31 @@ class TestCssParser:
         assert "color" in rules[0].properties
         assert "background" in rules[0].properties
 
+    def test_parse_param_comments(self):
+        """Test @param comments annotate the declaration before them."""
+        css = """.bar {
+  width: 50%; /* @param width_pct: u32 */
+  color: red; background: blue /* @param bg: &str */;
+  margin: 0;
+}
+"""
+        rule = self.parser.parse(css)[0]
+
+        assert [(d.name, d.param) for d in rule.declarations] == [
+            ("width", CssParam("width_pct", "u32")),
+            ("color", None),
+            ("background", CssParam("bg", "&str")),
+            ("margin", None),
+        ]
+
+    def test_param_names_sanitized(self):
+        """Test @param names that are Rust keywords become valid identifiers."""
+        css = ".bar { cursor: auto; /* @param type: &str */ }"
+
+        [declaration] = self.parser.parse(css)[0].declarations
+
+        assert declaration.param == CssParam("type_style", "&str")
+
     def test_parse_empty_rules(self):
         """Test parsing empty rules."""
         css = """
@@ -162,6 +404,70 @@ class TestCssParser:
         assert len(non_empty) >= 1
         assert any(r.properties.get("display") == "block" for r in rules)
 
+    def test_duplicate_declarations(self):
+        """Test repeated properties are kept in order as declarations."""
+        css = """
+        .box {
+            display: -webkit-box;
+            display: flex;
+            & > .item { color: red; }
+        }
+        """
+        rules = self.parser.parse(css)
+
+        assert [(d.name, d.value) for d in rules[0].declarations] == [
+            ("display", "-webkit-box"),
+            ("display", "flex"),
+        ]
+        assert rules[0].properties == {"display": "flex"}
+
+    def test_important_flag(self):
+        """Test !important is tracked separately from the value."""
+        css = """
+        .box {
+            color: red !important;
+            margin: 0;
+            &:hover { color: blue!important; }
+        }
+        """
+        rules = self.parser.parse(css)
+
+        assert [(d.value, d.important) for d in rules[0].declarations] == [
+            ("red", True),
+            ("0", False),
+        ]
+        assert rules[1].declarations[0].value == "blue"
+        assert rules[1].declarations[0].important
+
+    def test_source_spans(self):
+        """Test rules, declarations and keyframes carry source locations."""
+        css = """.card {
+  color: red;
+  &:hover { opacity: 0.9; }
+}
+@keyframes fade { from { opacity: 0; } }
+"""
+        rules = self.parser.parse(css, "card.css")
+
+        assert str(rules[0].span) == "card.css:1:1"
+        assert str(rules[0].declarations[0].span) == "card.css:2:3"
+        assert str(rules[1].span) == "card.css:3:3"
+        assert str(self.parser.keyframes[0].span) == "card.css:5:1"
+
+    def test_diagnostics(self):
+        """Test problems are reported as diagnostics with spans."""
+        css = """.card {
+  color: ;
+  .empty {}
+}
+"""
+        self.parser.parse(css, "card.css")
+        codes = [d.code for d in self.parser.diagnostics]
+
+        assert codes == ["empty-declaration", "empty-rule"]
+        assert str(self.parser.diagnostics[0].span) == "card.css:2:3"
+        assert str(self.parser.diagnostics[1].span) == "card.css:3:3"
+
     def test_parse_vendor_prefixes(self):
         """Test parsing vendor prefixes."""
         css = """
@@ -268,23 +574,438 @@ class TestCssParser:
         assert "btn_secondary" in variants
         assert "btn_large" in variants
 
-    def test_fallback_regex_parsing(self):
-        """Test fallback regex parsing when cssutils fails."""
-        # Create invalid CSS that might fail cssutils
+    def test_fallback_parsing(self):
+        """Test the built-in parser takes over when cssutils fails."""
         css = """
         .valid { color: red; }
         @invalid-rule { something: wrong; }
         .another { color: blue; }
         """
 
-        # Force regex parsing by using invalid CSS
+        def fail(css_content):
+            raise ValueError("forced failure")
+
         parser = CssParser()
-        rules = parser._parse_with_regex(css)
+        parser._parse_with_cssutils = fail
+        rules = parser.parse(css)
 
         # Should still parse valid rules
         assert len(rules) >= 2
         assert any(r.selector == ".valid" for r in rules)
         assert any(r.selector == ".another" for r in rules)
+        assert parser.diagnostics[0].code == "parser-fallback"
+
+
+    def test_fallback_parsing_tricky_input(self):
+        """Test the built-in parser with braces in strings, data urls and escapes."""
+        css = """
+        .icon::before { content: "{"; }
+        .logo { background: url(data:image/png;base64,iVBOR==); }
+        .w-1\\/2 { width: 50%; }
+        @media (max-width: 600px) { .logo { display: none; } }
+        """
+
+        def fail(css_content):
+            raise ValueError("forced failure")
+
+        parser = CssParser()
+        parser._parse_with_cssutils = fail
+        rules = parser.parse(css)
+
+        assert [(r.selector, r.media_query) for r in rules] == [
+            (".icon", None),
+            (".logo", None),
+            (".w-1\\/2", None),
+            (".logo", "(max-width: 600px)"),
+        ]
+        assert rules[0].properties == {"content": '"{"'}
+        assert rules[1].properties == {
+            "background": "url(data:image/png;base64,iVBOR==)"
+        }
+
+    def test_parse_font_face(self):
+        """Test @font-face rules are collected, including repeated src."""
+        css = """
+        @font-face {
+            font-family: "Inter";
+            src: url(inter.woff) format("woff");
+            src: url(inter.woff2) format("woff2");
+        }
+        .a { font-family: Inter; }
+        """
+        rules = self.parser.parse(css)
+
+        assert [r.selector for r in rules] == [".a"]
+        (font_face,) = self.parser.font_faces
+        assert [(d.name, d.value) for d in font_face.declarations] == [
+            ("font-family", '"Inter"'),
+            ("src", 'url(inter.woff) format("woff")'),
+            ("src", 'url(inter.woff2) format("woff2")'),
+        ]
+
+    def test_split_theme_tokens(self):
+        """Test theme tokens are split off :root and [data-theme] rules."""
+        css = """
+        :root { --brand: #3366ff; color-scheme: light; }
+        html[data-theme='dark'] { --brand: #99bbff; }
+        @media (prefers-contrast: more) { :root { --brand: blue; } }
+        .card { --pad: 4px; }
+        """
+        themes, rules = self.parser.split_theme_tokens(self.parser.parse(css))
+
+        assert themes == {
+            "default": {"--brand": "#3366ff"},
+            "dark": {"--brand": "#99bbff"},
+        }
+        assert [(r.selector, list(r.properties)) for r in rules] == [
+            (":root", ["color-scheme"]),
+            (":root", ["--brand"]),
+            (".card", ["--pad"]),
+        ]
+
+    def test_extract_variant_sets(self):
+        """Test variant rules are grouped by base and axis in axis order."""
+        css = """
+        .btn-lg { font-size: 18px; }
+        .btn { padding: 8px; }
+        .btn-primary { color: white; }
+        .btn:hover { opacity: 0.9; }
+        .btn-outline { border: 1px solid; }
+        .card { margin: 0; }
+        """
+        variant_sets, rules = self.parser.extract_variant_sets(self.parser.parse(css))
+
+        assert len(variant_sets) == 1
+        button = variant_sets[0]
+        assert button.name == "button"
+        assert [r.full_selector for r in button.base] == [".btn", ".btn:hover"]
+        assert list(button.axes) == ["variant", "size", "style"]
+        assert list(button.axes["size"]) == ["lg"]
+        assert [r.selector for r in rules] == [".card"]
+
+    def test_variant_sets_match_whole_tokens(self):
+        """Test variants are whole tokens and aliased bases share one set."""
+        css = """
+        .btn-smooth { transition: all 0.2s; }
+        .card-information { color: gray; }
+        .btn-linkage { color: blue; }
+        .btn-sm { font-size: 12px; }
+        .button { padding: 8px; }
+        .button-primary { color: white; }
+        """
+        variant_sets, rules = self.parser.extract_variant_sets(self.parser.parse(css))
+
+        assert [variant_set.name for variant_set in variant_sets] == ["button"]
+        button = variant_sets[0]
+        assert [r.selector for r in button.base] == [".button"]
+        assert {axis: list(v) for axis, v in button.axes.items()} == {
+            "variant": ["primary"],
+            "size": ["sm"],
+        }
+        assert [r.selector for r in rules] == [
+            ".btn-smooth",
+            ".card-information",
+            ".btn-linkage",
+        ]
+
+    def test_parse_file_imports(self, tmp_path):
+        """Test local imports are parsed in place, once, with cycles reported."""
+        (tmp_path / "base.css").write_text(
+            '@import "app.css"; @import url(https://example.com/x.css);'
+            "body { margin: 0; }"
+        )
+        (tmp_path / "theme.css").write_text(
+            '@import "base.css"; .theme { color: red; }'
+        )
+        (tmp_path / "app.css").write_text(
+            """
+            @import "base.css";
+            @import "theme.css";
+            @import "missing.css";
+            .app { display: block; }
+            """
+        )
+
+        rules = self.parser.parse_file(str(tmp_path / "app.css"))
+
+        assert [r.selector for r in rules] == ["body", ".theme", ".app"]
+        assert self.parser.imports == ["https://example.com/x.css"]
+        imports = [d for d in self.parser.diagnostics if d.code.startswith("import")]
+        assert [d.code for d in imports] == ["import-cycle", "import-not-found"]
+        assert "app.css -> base.css -> app.css" in imports[0].message
+
+    def test_import_media_conditions(self, tmp_path):
+        """Test imported rules apply under the import's media condition."""
+        (tmp_path / "print.css").write_text(
+            ".nav { display: none; } @media (min-width: 1px) { .page { margin: 0; } }"
+        )
+        (tmp_path / "grid.css").write_text(".grid { display: grid; }")
+        (tmp_path / "app.css").write_text(
+            "@import url(print.css) print;"
+            '@import "grid.css" supports(display: grid);'
+            ".app { & .title { display: block; } }"
+        )
+
+        rules = self.parser.parse_file(str(tmp_path / "app.css"))
+
+        assert [(r.selector, r.media_query) for r in rules] == [
+            (".nav", "print"),
+            (".page", "print and (min-width: 1px)"),
+            (".app", None),
+        ]
+        warnings = [d for d in self.parser.diagnostics if d.severity == "warning"]
+        assert [d.code for d in warnings] == ["unsupported-import"]
+
+    def test_media_query_lists(self, tmp_path):
+        """Test media query lists and negations are and-ed pairwise."""
+        (tmp_path / "part.css").write_text(
+            "@media (min-width: 1px) { .wide { margin: 0; } }"
+            "@media not print { .screen { margin: 0; } }"
+        )
+        (tmp_path / "hover.css").write_text(
+            "@media (min-width: 1px) { .touch { margin: 0; } }"
+        )
+        (tmp_path / "app.css").write_text(
+            '@import "part.css" screen, print;'
+            '@import "hover.css" not (hover: hover);'
+            "@media (a: 1) or (b: 1) { @media print { .x { margin: 0; } } }"
+        )
+
+        rules = self.parser.parse_file(str(tmp_path / "app.css"))
+
+        assert [(r.selector, r.media_query) for r in rules] == [
+            (".wide", "screen and (min-width: 1px), print and (min-width: 1px)"),
+            (".touch", "(not (hover: hover)) and (min-width: 1px)"),
+            (".x", "print and ((a: 1) or (b: 1))"),
+        ]
+        warnings = [d for d in self.parser.diagnostics if d.severity == "warning"]
+        assert [d.code for d in warnings] == ["unsupported-media"]
+        assert ".screen" in warnings[0].message
+
+
+class TestParseSelector:
+    """Test the structured selector parser."""
+
+    def test_compound_and_combinators(self):
+        """Test splitting compounds and recording combinators."""
+        selector = parse_selector(".card > .title + p ~ span a")
+
+        assert [c.to_css() for c in selector.compounds] == [
+            ".card",
+            ".title",
+            "p",
+            "span",
+            "a",
+        ]
+        assert [c.combinator for c in selector.compounds] == [
+            None,
+            ">",
+            "+",
+            "~",
+            " ",
+        ]
+        assert selector.to_css() == ".card > .title + p ~ span a"
+
+    def test_simple_selector_kinds(self):
+        """Test classifying simple selectors within a compound."""
+        selector = parse_selector('a#home.link[href^="http:"]:not(.x)::after')
+        parts = selector.compounds[0].parts
+
+        assert [(p.kind, p.name, p.argument) for p in parts] == [
+            ("type", "a", None),
+            ("id", "home", None),
+            ("class", "link", None),
+            ("attribute", 'href^="http:"', None),
+            ("pseudo_class", "not", ".x"),
+            ("pseudo_element", "after", None),
+        ]
+
+    def test_owner_and_nested_css(self):
+        """Test rendering selectors relative to the owning element."""
+        selector = parse_selector("div.card.active:hover > .title")
+
+        assert selector.owner == ".card"
+        assert selector.pseudo_selector == "hover"
+        assert selector.nested_css() == "div&.active:hover > .title"
+
+        root = parse_selector(":root")
+        assert root.owner == ":root"
+        assert root.pseudo_selector is None
+        assert root.nested_css() == "&"
+
+    def test_split_selector_list(self):
+        """Test splitting selector lists on top-level commas only."""
+        assert split_selector_list("h1, h2,h3") == ["h1", "h2", "h3"]
+        assert split_selector_list(".a:is(.b, .c), .d") == [".a:is(.b, .c)", ".d"]
+        assert split_selector_list('[title="a,b"], .x') == ['[title="a,b"]', ".x"]
+        assert split_selector_list(".single") == [".single"]
+
+    def test_invalid_selectors(self):
+        """Test that unparseable selectors raise ValueError."""
+        for invalid in ["", "> .a", ".a >", ".a, .b", ".a[x", ".a {"]:
+            try:
+                parse_selector(invalid)
+            except ValueError:
+                continue
+            raise AssertionError(f"{invalid!r} should not parse")
+
+
+class TestScssParser:
+    """Test ScssParser class."""
+
+    def setup_method(self):
+        """Set up test instance."""
+        self.parser = ScssParser()
+
+    def _selectors(self, rules):
+        return [rule.parsed_selector.to_css() for rule in rules]
+
+    def test_variables_and_comments(self):
+        """Test $variable substitution, !default and // comments."""
+        scss = """
+        // Brand colours
+        $primary: #007bff;
+        $radius: 4px !default;
+        $radius: 8px !default;
+        $bp: 768px;
+        .btn {
+            background: url(http://example.com/bg.png); // trailing comment
+            color: $primary;
+            border-radius: $radius;
+            @media (max-width: $bp) { width: 100%; }
+        }
+        .icon-#{$radius} { margin: 0; }
+        """
+        rules = self.parser.parse(scss)
+
+        assert rules[0].properties == {
+            "background": "url(http://example.com/bg.png)",
+            "color": "#007bff",
+            "border-radius": "4px",
+        }
+        assert rules[1].media_query == "(max-width: 768px)"
+        assert self._selectors(rules)[2] == ".icon-4px"
+        assert self.parser.variables == {
+            "primary": "#007bff",
+            "radius": "4px",
+            "bp": "768px",
+        }
+
+    def test_parent_suffix_selectors(self):
+        """Test &-suffix and &__element parent selectors."""
+        scss = """
+        .btn {
+            padding: 8px;
+            &-primary { color: blue; }
+            &__icon { width: 16px; }
+            &:hover { opacity: 0.9; }
+        }
+        """
+        rules = self.parser.parse(scss)
+
+        assert self._selectors(rules) == [
+            ".btn",
+            ".btn-primary",
+            ".btn__icon",
+            ".btn:hover",
+        ]
+
+    def test_mixins_and_includes(self):
+        """Test @mixin parameters, defaults, keyword args and @content."""
+        scss = """
+        @mixin size($padding, $font: 14px) {
+            padding: $padding;
+            font-size: $font;
+        }
+        @mixin hover { &:hover { @content; } }
+        .btn {
+            @include size(8px 16px);
+            @include hover { opacity: 0.9; }
+        }
+        .btn-large { @include size(12px, $font: 18px); }
+        """
+        rules = self.parser.parse(scss)
+
+        assert self._selectors(rules) == [".btn", ".btn:hover", ".btn-large"]
+        assert rules[0].properties == {"padding": "8px 16px", "font-size": "14px"}
+        assert rules[1].properties == {"opacity": "0.9"}
+        assert rules[2].properties == {"padding": "12px", "font-size": "18px"}
+
+    def test_extend(self):
+        """Test @extend of classes and placeholder selectors."""
+        scss = """
+        %message { border: 1px solid gray; }
+        .alert { padding: 8px; }
+        .alert:hover { opacity: 1; }
+        .error { @extend %message; @extend .alert; color: red; }
+        """
+        rules = self.parser.parse(scss)
+
+        assert self._selectors(rules) == [
+            ".error",
+            ".alert",
+            ".error",
+            ".alert:hover",
+            ".error:hover",
+            ".error",
+        ]
+        assert rules[0].properties == {"border": "1px solid gray"}
+        assert rules[-1].properties == {"color": "red"}
+
+    def test_parse_file_partials(self, tmp_path):
+        """Test imports resolve to SCSS partials by name."""
+        (tmp_path / "_buttons.scss").write_text(".btn { &:hover { opacity: 1; } }")
+        (tmp_path / "main.scss").write_text('@import "buttons"; .app { color: red; }')
+
+        rules = self.parser.parse_file(str(tmp_path / "main.scss"))
+
+        assert [(r.selector, r.pseudo_selector) for r in rules] == [
+            (".btn", "hover"),
+            (".app", None),
+        ]
+
+    def test_partials_share_scope(self, tmp_path):
+        """Test partials are imported in place, sharing variables and mixins."""
+        (tmp_path / "_vars.scss").write_text(
+            "$primary: #336699 !default;\n"
+            "@mixin rounded($radius: 4px) { border-radius: $radius; }\n"
+            ".base { color: $primary; }\n"
+        )
+        (tmp_path / "main.scss").write_text(
+            ".reset { margin: 0; }\n"
+            "$primary: red;\n"
+            '@import "vars";\n'
+            ".btn { color: $primary; @include rounded(2px); }\n"
+        )
+
+        rules = self.parser.parse_file(str(tmp_path / "main.scss"))
+
+        assert self._selectors(rules) == [".reset", ".base", ".btn"]
+        assert rules[1].properties == {"color": "red"}
+        assert rules[2].properties == {"color": "red", "border-radius": "2px"}
+        assert self.parser.diagnostics == []
+        assert rules[1].span.file == str(tmp_path / "_vars.scss")
+        assert (rules[2].span.file, rules[2].span.line) == (
+            str(tmp_path / "main.scss"),
+            4,
+        )
+
+    def test_diagnostics(self):
+        """Test unknown mixins and unsupported directives are reported."""
+        scss = """// header
+.btn { @include missing; }
+@each $size in sm, lg { .x { color: red; } }
+"""
+        self.parser.parse(scss, "btn.scss")
+        diagnostics = self.parser.diagnostics
+
+        assert [d.code for d in diagnostics] == [
+            "unknown-mixin",
+            "unsupported-directive",
+            "empty-rule",
+        ]
+        assert str(diagnostics[0].span) == "btn.scss:2:8"
+        assert str(diagnostics[1].span) == "btn.scss:3:1"
 
 
 class TestCssRule:
@@ -333,6 +1054,21 @@ class TestCssRule:
         assert rule.selector == ".button"
 
 
+    def test_declarations_and_properties_derived(self):
+        """Test declarations and properties are derived from each other."""
+        rule = CssRule(
+            selector=".box",
+            declarations=[
+                CssDeclaration("display", "-webkit-box"),
+                CssDeclaration("display", "flex"),
+            ],
+        )
+        assert rule.properties == {"display": "flex"}
+
+        rule = CssRule(selector=".box", properties={"color": "red"})
+        assert rule.declarations == [CssDeclaration("color", "red")]
+
+
 class TestCssKeyframe:
     """Test CssKeyframe class."""
 
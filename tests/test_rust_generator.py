@@ -0,0 +1,679 @@
+"""Tests for the stylist output produced by RustGenerator."""
+
+import re
+
+from css_to_rust.generator import RustGenerator
+from css_to_rust.parser import CssParser, CssRule
+from css_to_rust.targets import get_target
+from css_to_rust.utils import module_class_name
+
+
+def parse_stylist_block(rust_code):
+    """Parse the raw string passed to Style::new back into nested blocks.
+
+    Returns a dict with the top-level ``declarations`` and a ``blocks`` list of
+    ``(selector, nested_dict)`` pairs, failing on unbalanced braces.
+    """
+    match = re.search(r'r#"(.*?)"#', rust_code, re.DOTALL)
+    assert match, "no raw string literal found"
+    body = match.group(1)
+
+    root = {"declarations": [], "blocks": []}
+    stack = [root]
+    buffer = ""
+
+    for char in body:
+        if char == "{":
+            selector = buffer.strip()
+            assert selector, "block without selector"
+            block = {"declarations": [], "blocks": []}
+            stack[-1]["blocks"].append((selector, block))
+            stack.append(block)
+            buffer = ""
+        elif char == "}":
+            assert not buffer.strip(), f"unterminated declaration: {buffer!r}"
+            stack.pop()
+            assert stack, "unbalanced closing brace"
+            buffer = ""
+        elif char == ";":
+            name, _, value = buffer.partition(":")
+            assert name.strip() and value.strip(), f"bad declaration: {buffer!r}"
+            stack[-1]["declarations"].append((name.strip(), value.strip()))
+            buffer = ""
+        else:
+            buffer += char
+
+    assert len(stack) == 1, "unbalanced opening brace"
+    assert not buffer.strip(), f"trailing content: {buffer!r}"
+    return root
+
+
+class TestStylistOutput:
+    """Test the CSS emitted inside generated style functions."""
+
+    def setup_method(self):
+        """Set up test instance."""
+        self.generator = RustGenerator()
+        self.parser = CssParser()
+
+    def _generate(self, css):
+        rules = self.parser.parse(css)
+        return self.generator.generate_style_function("button", rules)
+
+    def test_base_declarations_at_top_level(self):
+        """Test that the component's own declarations are not wrapped."""
+        code = self._generate(".button { display: flex; cursor: pointer; }")
+        block = parse_stylist_block(code)
+
+        assert block["declarations"] == [("display", "flex"), ("cursor", "pointer")]
+        assert block["blocks"] == []
+
+    def test_pseudo_class_nesting(self):
+        """Test that pseudo-classes become valid & nested blocks."""
+        code = self._generate(
+            """
+            .button:hover { cursor: pointer; }
+            .button { display: flex; }
+            """
+        )
+        block = parse_stylist_block(code)
+
+        assert block["declarations"] == []
+        assert [selector for selector, _ in block["blocks"]] == ["&:hover", "&"]
+        assert block["blocks"][0][1]["declarations"] == [("cursor", "pointer")]
+        assert block["blocks"][1][1]["declarations"] == [("display", "flex")]
+        assert "&: hover" not in code
+        assert "{;" not in code
+
+    def test_rules_keep_source_order(self):
+        """Test declarations after a nested block stay after it, in ``& { }``."""
+        code = self._generate(
+            """
+            .button { display: flex; }
+            .button:where(:hover) { color: red; }
+            .button { color: blue; }
+            """
+        )
+        block = parse_stylist_block(code)
+
+        assert block["declarations"] == [("display", "flex")]
+        assert block["blocks"] == [
+            ("&:where(:hover)", {"declarations": [("color", "red")], "blocks": []}),
+            ("&", {"declarations": [("color", "blue")], "blocks": []}),
+        ]
+
+    def test_pseudo_element_and_functional_pseudo_classes(self):
+        """Test pseudo-elements, :not() chains and :nth-child() arguments."""
+        code = self._generate(
+            """
+            .button::before { content: ""; }
+            .button:not(.disabled):focus-visible { outline: none; }
+            .button:nth-child(2n+1) { opacity: 0.5; }
+            """
+        )
+        block = parse_stylist_block(code)
+
+        assert [selector for selector, _ in block["blocks"]] == [
+            "&::before",
+            "&:not(.disabled):focus-visible",
+            "&:nth-child(2n+1)",
+        ]
+
+    def test_relative_selectors(self):
+        """Test descendant, child, sibling and attribute selectors."""
+        code = self._generate(
+            """
+            .card { padding: 8px; }
+            .card > .title { font-weight: bold; }
+            .card .icon { width: 16px; }
+            .card + .card { margin-top: 8px; }
+            .card[disabled] { opacity: 0.5; }
+            """
+        )
+        block = parse_stylist_block(code)
+
+        assert block["declarations"] == [("padding", "var(--spacing-sm)")]
+        assert [selector for selector, _ in block["blocks"]] == [
+            "& > .title",
+            "& .icon",
+            "& + .card",
+            "&[disabled]",
+        ]
+
+    def test_selector_list_shares_block(self):
+        """Test that list members in the same function share one block."""
+        code = self._generate(
+            """
+            .button, .button:hover, .button:focus-visible { color: white; }
+            """
+        )
+        block = parse_stylist_block(code)
+
+        assert block["declarations"] == []
+        assert [selector for selector, _ in block["blocks"]] == [
+            "&, &:hover, &:focus-visible"
+        ]
+
+    def test_native_nesting_round_trip(self):
+        """Test that native nesting input maps onto stylist & nesting."""
+        code = self._generate(
+            """
+            .button {
+                display: flex;
+                &:hover { cursor: pointer; }
+                & > .icon { width: 16px; }
+                @media (max-width: 600px) { width: 100%; }
+            }
+            """
+        )
+        block = parse_stylist_block(code)
+
+        assert block["declarations"] == [("display", "flex")]
+        assert [selector for selector, _ in block["blocks"]] == [
+            "&:hover",
+            "& > .icon",
+            "@media (max-width: 600px)",
+        ]
+
+    def test_media_query_nesting(self):
+        """Test that media queries wrap declarations and nested pseudo blocks."""
+        rules = [
+            CssRule(
+                selector=".button",
+                properties={"width": "100%"},
+                media_query="(max-width: 768px)",
+            ),
+            CssRule(
+                selector=".button",
+                properties={"opacity": "0.8"},
+                media_query="(max-width: 768px)",
+                pseudo_selector="hover",
+            ),
+        ]
+        code = self.generator.generate_style_function("button", rules)
+        block = parse_stylist_block(code)
+
+        media_selector, media_block = block["blocks"][0]
+        assert media_selector == "@media (max-width: 768px)"
+        assert media_block["declarations"] == [("width", "100%")]
+        assert media_block["blocks"][0][0] == "&:hover"
+
+    def test_supports_nesting(self):
+        """Test that @supports blocks nest inside the matching media query."""
+        code = self._generate(
+            """
+            .button { display: block; }
+            @supports (display: grid) {
+                .button { display: grid; }
+                @media (min-width: 600px) {
+                    .button:hover { gap: 16px; }
+                }
+            }
+            """
+        )
+        block = parse_stylist_block(code)
+
+        assert block["declarations"] == [("display", "block")]
+        supports, media = block["blocks"]
+        supports_selector, supports_block = supports
+        media_selector, media_block = media
+        assert supports_selector == "@supports (display: grid)"
+        assert supports_block["declarations"] == [("display", "grid")]
+        assert media_selector == "@media (min-width: 600px)"
+        nested_selector, nested_block = media_block["blocks"][0]
+        assert nested_selector == "@supports (display: grid)"
+        assert nested_block["blocks"][0][0] == "&:hover"
+
+    def test_container_query_nesting(self):
+        """Test container queries nest inside media queries, mapping breakpoints.
+
+        Custom properties aren't allowed in query conditions, so breakpoints
+        resolve to their literal lengths.
+        """
+        code = self._generate(
+            """
+            @container card (min-width: var(--breakpoint-md)) {
+                .button { width: 100%; }
+            }
+            @media print {
+                @container (width > 500px) { .button { display: none; } }
+            }
+            """
+        )
+        block = parse_stylist_block(code)
+
+        assert [selector for selector, _ in block["blocks"]] == [
+            "@container card (min-width: 768px)",
+            "@media print",
+        ]
+        media_block = block["blocks"][1][1]
+        assert media_block["blocks"][0][0] == "@container (width > 500px)"
+
+    def test_layer_wrappers(self):
+        """Test @layer blocks wrap media queries and nested selectors."""
+        code = self._generate(
+            """
+            @layer components {
+                .button { float: left; }
+                @media (max-width: 600px) {
+                    .button:hover { opacity: 0.8; }
+                }
+            }
+            """
+        )
+        block = parse_stylist_block(code)
+
+        ((layer_selector, layer_block),) = block["blocks"]
+        assert layer_selector == "@layer components"
+        assert layer_block["declarations"] == [("float", "left")]
+        media_selector, media_block = layer_block["blocks"][0]
+        assert media_selector == "@media (max-width: 600px)"
+        assert media_block["blocks"][0][0] == "&:hover"
+
+    def test_at_rule_blocks_keep_source_order(self):
+        """Test sibling at-rule blocks keep their order, which decides the cascade."""
+        code = self._generate(
+            """
+            @media (max-width: 600px) { .button { display: block; } }
+            @supports (display: grid) { .button { display: grid; } }
+            @container (width > 400px) { .button { float: left; } }
+            @media (max-width: 600px) { .button:hover { opacity: 0.8; } }
+            """
+        )
+        block = parse_stylist_block(code)
+
+        assert [selector for selector, _ in block["blocks"]] == [
+            "@media (max-width: 600px)",
+            "@supports (display: grid)",
+            "@container (width > 400px)",
+            "@media (max-width: 600px)",
+        ]
+
+    def test_global_module_layer_order(self):
+        """Test the declared layer order is stated ahead of the global styles."""
+        rules = self.parser.parse(
+            """
+            @layer base, components;
+            @layer components { .button { float: left; } }
+            @layer base { body { margin: 0; } }
+            """
+        )
+        assert self.parser.layers == ["base", "components"]
+        global_rules, _ = self.parser.split_global_rules(rules)
+        code = self.generator.generate_global_module(
+            global_rules, layers=self.parser.layers
+        )
+
+        assert 'r#"\n    @layer base, components;\n\n    @layer base {' in code
+
+    def test_global_module(self):
+        """Test global rules keep full selectors and source order."""
+        rules = self.parser.parse(
+            """
+            body { margin: 0; }
+            a:visited { color: purple; }
+            @media (max-width: 600px) { html { font-size: 14px; } }
+            a { color: inherit; }
+            """
+        )
+        code = self.generator.generate_global_module(rules)
+        block = parse_stylist_block(code)
+
+        assert block["declarations"] == []
+        assert [selector for selector, _ in block["blocks"]] == [
+            "body",
+            "a:visited",
+            "@media (max-width: 600px)",
+            "a",
+        ]
+        assert block["blocks"][2][1]["blocks"][0][0] == "html"
+        assert "GlobalStyle::new(GLOBAL_CSS)" in code
+
+    def test_variant_function(self):
+        """Test variant sets become enums and a composing function."""
+        rules = self.parser.parse(
+            """
+            .btn { display: flex; }
+            .btn-primary { color: white; }
+            .btn-sm { float: left; }
+            """
+        )
+        [variant_set], _ = self.parser.extract_variant_sets(rules)
+        code = self.generator.generate_variant_function(variant_set)
+
+        assert "pub enum ButtonVariant {\n    #[default]\n    Default,\n" in code
+        assert "    Primary,\n}" in code
+        assert "pub enum ButtonSize {" in code
+        assert (
+            "pub fn button(variant: ButtonVariant, size: ButtonSize) -> Style {"
+            in code
+        )
+        assert "ButtonVariant::Default => \"\"," in code
+        assert "ButtonSize::Sm => r#\"\n        float: left;\n    \"#," in code
+        assert "[base_css, variant_css, size_css].concat()" in code
+        assert parse_stylist_block(code)["declarations"] == [("display", "flex")]
+
+    def test_style_macro(self):
+        """Test macro output invokes style! or css! with escaped dollars."""
+        rules = self.parser.parse('.button { content: "$"; }')
+
+        self.generator.style_macro = "style"
+        code = self.generator.generate_style_function("button", rules)
+        assert "use stylist::{style, Style};" in code
+        assert "pub fn button() -> Style {\n    style!(\n" in code
+        assert '.expect("Failed to create button styles")' in code
+        assert parse_stylist_block(code)["declarations"] == [("content", '"$$"')]
+
+        self.generator.style_macro = "css"
+        code = self.generator.generate_style_function("button", rules)
+        assert "pub fn button() -> StyleSource {\n    css!(\n" in code
+        assert "expect" not in code
+
+    def test_raw_strings_outlast_css_quotes(self):
+        """Test raw string literals take enough hashes for the CSS they hold."""
+        rules = self.parser.parse('.link[href^="#"] { content: "##"; }')
+
+        code = self.generator.generate_style_function("link", rules)
+        assert '    Style::new(\n        r###"\n' in code
+        assert '\n    "###,\n' in code
+
+        self.generator.style_macro = "css"
+        code = self.generator.generate_style_function("link", rules)
+        assert '    css!(\n        r###"\n' in code
+
+        code = self.generator.generate_global_module(rules)
+        assert 'const GLOBAL_CSS: &str = r###"\n' in code
+
+        self.generator.target = get_target("leptos")
+        code = self.generator.generate_style_function("link", rules)
+        assert 'const LINK_CSS: &str = r###"\n' in code
+
+    def test_cached_styles(self):
+        """Test cached functions build their style once per thread."""
+        self.generator.cache_styles = True
+        rules = self.parser.parse(".btn { display: flex; } .btn-sm { float: left; }")
+
+        code = self.generator.generate_style_function("card", rules[:1])
+        assert "    thread_local! {\n        static STYLE: Style = Style::new(" in code
+        assert '.expect("Failed to create card styles");\n    }' in code
+        assert code.endswith("    STYLE.with(Style::clone)\n}")
+        assert parse_stylist_block(code)["declarations"] == [("display", "flex")]
+
+        [variant_set], _ = self.parser.extract_variant_sets(rules)
+        code = self.generator.generate_variant_function(variant_set)
+        assert "use std::collections::HashMap;" in code
+        assert "static STYLES: RefCell<HashMap<ButtonSize, Style>>" in code
+        assert ".entry(size)" in code
+        assert "Hash, Default)]" in code
+
+    def test_style_errors(self):
+        """Test functions can return a Result, or pair it with a panicking one."""
+        self.generator.style_errors = "result"
+        code = self._generate(".button { display: flex; }")
+        assert "pub fn button() -> Result<Style, stylist::Error> {" in code
+        assert "expect" not in code
+
+        self.generator.style_errors = "both"
+        utilities = self.generator.generate_utility_functions({})
+        assert "pub fn try_hidden() -> Result<Style, stylist::Error> {" in (
+            utilities["hidden"]
+        )
+        assert (
+            "pub fn hidden() -> Style {\n"
+            '    try_hidden().expect("Failed to create hidden styles")\n}'
+        ) in utilities["hidden"]
+
+        self.generator.style_macro = "css"
+        code = self._generate(".button { display: flex; }")
+        assert "pub fn button() -> StyleSource {" in code
+        assert "try_button" not in code
+
+    def test_cached_style_errors(self):
+        """Test cached Result functions keep the style and return errors."""
+        self.generator.cache_styles = True
+        self.generator.style_errors = "both"
+        rules = self.parser.parse(".btn { display: flex; } .btn-sm { float: left; }")
+
+        code = self.generator.generate_style_function("card", rules[:1])
+        assert "use std::cell::RefCell;\nuse stylist::Style;" in code
+        assert "pub fn try_card() -> Result<Style, stylist::Error> {" in code
+        assert "static STYLES: RefCell<Option<Style>> = RefCell::default();" in code
+        assert "if let Some(style) = styles.borrow().as_ref() {" in code
+        assert "}?;\n        *styles.borrow_mut() = Some(style.clone());" in code
+        assert "Result<Style, stylist::Error>>" not in code
+        assert parse_stylist_block(code)["declarations"] == [("display", "flex")]
+
+        [variant_set], _ = self.parser.extract_variant_sets(rules)
+        code = self.generator.generate_variant_function(variant_set)
+        assert "static STYLES: RefCell<HashMap<ButtonSize, Style>>" in code
+        assert "if let Some(style) = styles.borrow().get(&size) {" in code
+        assert "styles.borrow_mut().insert(size, style.clone());" in code
+        assert 'try_button(size).expect("Failed to create button styles")' in code
+
+    def test_global_style_errors(self):
+        """Test global and theme styles follow the style error handling."""
+        rules = self.parser.parse("body { margin: 0; }")
+
+        self.generator.style_errors = "result"
+        code = self.generator.generate_global_module(rules)
+        assert (
+            "pub fn global_styles() -> Result<GlobalStyle, stylist::Error> {\n"
+            "    GlobalStyle::new(GLOBAL_CSS)\n}"
+        ) in code
+        assert "expect" not in code
+
+        self.generator.style_errors = "both"
+        code = self.generator.generate_theme_module({"default": {"--gap": "4px"}})
+        assert (
+            "pub fn try_theme_style(theme: &Theme)"
+            " -> Result<GlobalStyle, stylist::Error> {"
+        ) in code
+        assert (
+            "pub fn theme_style(theme: &Theme) -> GlobalStyle {\n"
+            '    try_theme_style(theme).expect("Failed to create theme styles")\n}'
+        ) in code
+
+    def test_parameterised_function(self):
+        """Test parameters are interpolated with format! and braces escaped."""
+        self.generator.mappings.parameters = {"background-color": "&str"}
+        self.generator.cache_styles = True
+        css = """.bar {
+            width: 50%; /* @param width_pct: u32 */
+            background-color: red;
+            &:hover { opacity: 0.5; /* @param opacity: f32 */ }
+        }"""
+        code = self._generate(css)
+
+        assert (
+            "pub fn button(width_pct: u32, background_color: &str, opacity: f32)"
+            " -> Style {\n    Style::new(format!(\n" in code
+        )
+        assert "thread_local!" not in code
+        assert "        width: {width_pct}%;\n" in code
+        assert "        background-color: {background_color};\n" in code
+        assert (
+            '    "#,\n        background_color = background_color.replace('
+            "['{', '}', ';', '<', '\\\\', '\\n'], \"\"),\n    ))"
+        ) in code
+        assert "        &:hover {{\n            opacity: {opacity};\n        }}" in code
+
+    def test_scoped_targets(self):
+        """Test non-stylist targets scope CSS to a class mounted by a component."""
+        rules = self.parser.parse(".button { float: left; &:hover { clear: both; } }")
+
+        self.generator.target = get_target("leptos")
+        code = self.generator.generate_style_function("button", rules)
+        class_name = re.search(r'pub const BUTTON: &str = "(button-\w{8})";', code)
+        assert class_name
+        assert "use leptos_meta::Style;" in code
+        assert "view! { <Style id=BUTTON>{BUTTON_CSS}</Style> }" in code
+        block = parse_stylist_block(code)
+        assert block["blocks"][0][0] == f".{class_name.group(1)}"
+        assert block["blocks"][0][1]["declarations"] == [("float", "left")]
+        assert block["blocks"][0][1]["blocks"][0][0] == "&:hover"
+
+        self.generator.target = get_target("dioxus")
+        code = self.generator.generate_style_function("button", rules)
+        assert "pub fn ButtonStyle() -> Element {" in code
+        assert "rsx! { style { id: BUTTON, {BUTTON_CSS} } }" in code
+        assert "stylist" not in code
+
+    def test_yew_component(self):
+        """Test components take variant and parameter props and apply the style."""
+        rules = self.parser.parse(
+            """
+            .btn { display: flex; }
+            .btn-primary { float: left; }
+            .btn-lg { clear: both; }
+            """
+        )
+        [variant_set], _ = self.parser.extract_variant_sets(rules)
+        code = self.generator.generate_component("button", variant_set=variant_set)
+
+        assert "#[function_component(Button)]" in code
+        assert "    #[prop_or_default]\n    pub variant: ButtonVariant," in code
+        assert "    #[prop_or_default]\n    pub size: ButtonSize," in code
+        assert "    pub children: Html," in code
+        assert "let style = button(props.variant, props.size);" in code
+        assert "        <button\n            class={classes!(style, props.class" in code
+
+        self.generator.style_errors = "result"
+        rules = self.parser.parse(".bar { color: red; } /* @param color: &str */")
+        code = self.generator.generate_component("bar", rules)
+        assert "    pub color: String,\n" in code
+        assert "let style = bar(&props.color).ok();" in code
+        assert "        <div\n" in code
+
+    def test_css_module(self):
+        """Test class selectors are hashed and exposed as constants."""
+        rules = self.parser.parse(
+            """
+            body { margin: 0; }
+            .btn-primary:hover > .icon { color: red; }
+            a[href$=".pdf"] { color: blue; }
+            """
+        )
+        rules, classes = self.generator.hash_class_names(rules, "app.css")
+        assert list(classes) == ["btn-primary", "icon"]
+        assert classes["icon"] == module_class_name("icon", "app.css")
+
+        css = self.generator.generate_stylesheet(rules)
+        assert "body {\n    margin: 0;\n}" in css
+        assert f".{classes['btn-primary']}:hover > .{classes['icon']} {{" in css
+        assert 'a[href$=".pdf"] {' in css
+
+        code = self.generator.generate_css_module(classes, "app.css")
+        assert 'pub const STYLESHEET: &str = include_str!("app.css");' in code
+        assert f'pub const BTN_PRIMARY: &str = "{classes["btn-primary"]}";' in code
+        assert "Style::new" not in code
+
+    def test_template_overrides(self, tmp_path):
+        """Test templates in a template directory replace the packaged ones."""
+        (tmp_path / "style_function.rs.j2").write_text(
+            "// {{ selectors|join(', ') }} at line {{ spans[0].line }}\n{{ items }}\n"
+        )
+        rules = self.parser.parse(".card {\n  margin: 0;\n}\n.card:hover { top: 0; }")
+        self.generator.use_templates(str(tmp_path))
+
+        code = self.generator.generate_style_function("card", rules)
+        assert code.startswith("// .card, .card:hover at line 1\n/// Styles for")
+        # Templates the directory lacks still come from the package
+        mod_file = self.generator.generate_mod_file(["card"])
+        assert mod_file.startswith("//! Style modules\n\npub mod card;\n")
+
+        module = self.generator.generate_component_module("card", {"card": code})
+        assert module.startswith("//! Card component styles\n")
+
+    def test_rustdoc(self, tmp_path):
+        """Test functions are documented with their selectors, source and CSS."""
+        css_file = tmp_path / "button.css"
+        css_file.write_text(
+            ".btn {\n  color: red;\n}\n"
+            "@media (max-width: 600px) {\n  .btn:hover { color: blue; }\n}\n"
+        )
+        rules = self.parser.parse_file(str(css_file))
+        code = self.generator.generate_style_function("btn", rules)
+
+        assert "//! Btn styles\n" in code
+        assert "/// Styles for `.btn`, `.btn:hover`.\n" in code
+        assert f"/// Source: `{css_file}`, lines 1-5\n" in code
+        assert (
+            "/// ```css\n"
+            "/// .btn { color: red }\n"
+            "/// @media (max-width: 600px) { .btn:hover { color: blue } }\n"
+            "/// ```\npub fn btn() -> Style {"
+        ) in code
+
+        module = self.generator.generate_component_module("button", {"btn": code})
+        assert module.startswith("//! Button component styles\n\nuse stylist")
+        assert "/// Styles for `.btn`, `.btn:hover`.\n" in module
+
+    def test_rustdoc_everywhere(self):
+        """Test global, theme, utility and component items are documented too."""
+        rules = self.parser.parse("body { margin: 0; }")
+        self.generator.style_errors = "both"
+
+        code = self.generator.generate_global_module(rules)
+        doc = "/// Styles for `body`.\n///\n/// Source: line 1\n"
+        css = "///\n/// ```css\n/// body { margin: 0 }\n/// ```\n"
+        assert f"{doc}{css}pub fn try_global_styles()" in code
+        assert code.count(doc) == 2
+        assert "/// Mounts the global styles" in code
+        code = self.generator.generate_global_module([], layers=["base"])
+        assert "/// Global styles declaring the cascade layer order.\n" in code
+
+        code = self.generator.generate_theme_module({"default": {"--gap": "4px"}})
+        assert code.count("/// Declares the tokens of `theme`") == 2
+
+        utilities = self.generator.generate_utility_functions({})
+        assert (
+            "/// Utility styles for `display: none`.\npub fn try_hidden()"
+            in utilities["hidden"]
+        )
+
+        code = self.generator.generate_component("bar", rules)
+        assert "/// Renders a `<div>` styled with `bar`.\n#[function_component" in code
+
+    def test_duplicate_declarations_kept_in_order(self):
+        """Test that fallback declarations survive generation in source order."""
+        css = """
+        .button {
+            display: -webkit-box;
+            display: flex;
+            background: red;
+            background: linear-gradient(red, blue);
+        }
+        """
+        block = parse_stylist_block(self._generate(css))
+
+        assert block["declarations"] == [
+            ("display", "-webkit-box"),
+            ("display", "flex"),
+            ("background", "red"),
+            ("background", "linear-gradient(red, blue)"),
+        ]
+
+    def test_important_kept_and_value_mapped(self):
+        """Test that !important survives and the bare value is still mapped."""
+        css = """
+        .button { padding: 8px !important; color: red; }
+        .button:hover { color: black ! IMPORTANT; }
+        """
+        block = parse_stylist_block(self._generate(css))
+
+        assert block["declarations"] == [
+            ("padding", "var(--spacing-sm) !important"),
+            ("color", "red"),
+        ]
+        assert block["blocks"][0][1]["declarations"] == [
+            ("color", "var(--color-text-primary) !important")
+        ]
+
+    def test_generated_function_is_formatted(self):
+        """Test that the function name and CSS are substituted."""
+        code = self._generate(".button { display: flex; }")
+
+        assert "pub fn button() -> Style {" in code
+        assert '.expect("Failed to create button styles")' in code
+        assert "{function_name}" not in code
+        assert "{css_content" not in code
@@ -0,0 +1,226 @@
+"""Conformance tests for the CSS Syntax tokenizer and parser."""
+
+
+from css_to_rust.tokenizer import (
+    AtRule,
+    Declaration,
+    QualifiedRule,
+    parse_declarations,
+    parse_stylesheet,
+    serialize,
+    tokenize,
+)
+
+
+def _tokens(css):
+    """Tokenize and drop whitespace, as ``(type, value)`` pairs."""
+    return [(t.type, t.value) for t in tokenize(css) if t.type != "whitespace"]
+
+
+class TestTokenize:
+    """Test the tokenizer against CSS Syntax Level 3."""
+
+    def test_idents_functions_and_at_keywords(self):
+        """Test names, functions and at-keywords."""
+        assert _tokens("color --custom -moz-box calc( @media") == [
+            ("ident", "color"),
+            ("ident", "--custom"),
+            ("ident", "-moz-box"),
+            ("function", "calc"),
+            ("at-keyword", "media"),
+        ]
+
+    def test_hashes(self):
+        """Test id-type hashes are told apart from unrestricted ones."""
+        tokens = [t for t in tokenize("#main #1a2b3c") if t.type == "hash"]
+
+        assert [(t.value, t.is_id) for t in tokens] == [
+            ("main", True),
+            ("1a2b3c", False),
+        ]
+
+    def test_numbers(self):
+        """Test numbers, percentages and dimensions."""
+        css = "12 -0.5 +.5em 1e3px 50% 1.5e-2"
+        tokens = [t for t in tokenize(css) if t.type != "whitespace"]
+
+        assert [(t.type, t.value, t.unit) for t in tokens] == [
+            ("number", 12.0, None),
+            ("number", -0.5, None),
+            ("dimension", 0.5, "em"),
+            ("dimension", 1000.0, "px"),
+            ("percentage", 50.0, None),
+            ("number", 0.015, None),
+        ]
+
+    def test_strings(self):
+        """Test quoted strings with braces, escapes and escaped newlines."""
+        assert _tokens("'a{b}' \"q\\\"uote\" 'line\\\ncontinued'") == [
+            ("string", "a{b}"),
+            ("string", 'q"uote'),
+            ("string", "linecontinued"),
+        ]
+
+    def test_bad_string(self):
+        """Test an unescaped newline ends the string as a bad string."""
+        errors = []
+        tokens = tokenize("'open\n.next", errors)
+
+        assert tokens[0].type == "bad-string"
+        assert tokens[1].type == "whitespace"
+        assert errors[0].message == "Newline in string"
+
+    def test_escapes(self):
+        """Test hex escapes, the whitespace they consume and invalid code points."""
+        assert _tokens("\\31 0px .a\\:b") == [
+            ("ident", "10px"),
+            ("delim", "."),
+            ("ident", "a:b"),
+        ]
+        assert _tokens("\\0 \\110000 \\E9t") == [("ident", "\ufffd\ufffdét")]
+
+    def test_urls(self):
+        """Test unquoted urls, quoted urls and bad urls."""
+        css = "url(data:image/png;base64,iVBOR==) url( 'a.png' ) url(a b)"
+        errors = []
+        tokens = [t for t in tokenize(css, errors) if t.type != "whitespace"]
+
+        assert (tokens[0].type, tokens[0].value) == (
+            "url",
+            "data:image/png;base64,iVBOR==",
+        )
+        assert (tokens[1].type, tokens[1].value) == ("function", "url")
+        assert tokens[-1].type == "bad-url"
+        assert tokens[-1].text == "url(a b)"
+        assert errors[0].message == "Invalid url()"
+
+    def test_comments_cdo_cdc_and_delims(self):
+        """Test comments are dropped and punctuation is tokenized."""
+        assert _tokens("<!-- a/**/b /* x */ --> > ! ; : , [ ] ( ) { }") == [
+            ("CDO", "<!--"),
+            ("ident", "a"),
+            ("ident", "b"),
+            ("CDC", "-->"),
+            ("delim", ">"),
+            ("delim", "!"),
+            ("semicolon", ";"),
+            ("colon", ":"),
+            ("comma", ","),
+            ("[", "["),
+            ("]", "]"),
+            ("(", "("),
+            (")", ")"),
+            ("{", "{"),
+            ("}", "}"),
+        ]
+
+    def test_unclosed_comment(self):
+        """Test an unclosed comment runs to the end of input."""
+        errors = []
+        tokens = tokenize("a /* never closed", errors)
+
+        assert [t.type for t in tokens] == ["ident", "whitespace"]
+        assert errors[0].message == "Unclosed comment"
+
+    def test_offsets_and_raw_text(self):
+        """Test tokens keep their offset and source text."""
+        tokens = tokenize(".a\\:b { }")
+
+        assert [(t.offset, t.text) for t in tokens[:2]] == [(0, "."), (1, "a\\:b")]
+
+
+class TestParseStylesheet:
+    """Test the rule and declaration parser."""
+
+    def test_braces_in_strings_and_urls(self):
+        """Test braces and semicolons inside strings and urls stay in values."""
+        css = """
+        .a[data-x="{"]::before {
+            content: "}";
+            background: url(data:image/svg+xml;utf8,<svg></svg>) no-repeat;
+        }
+        """
+        (rule,) = parse_stylesheet(css)
+
+        assert serialize(rule.prelude) == '.a[data-x="{"]::before'
+        assert [(d.name, serialize(d.value)) for d in rule.content] == [
+            ("content", '"}"'),
+            ("background", "url(data:image/svg+xml;utf8,<svg></svg>) no-repeat"),
+        ]
+
+    def test_nested_at_rules(self):
+        """Test at-rules nest inside at-rules to any depth."""
+        css = """
+        @media screen {
+            @supports (display: grid) {
+                .grid { display: grid; }
+            }
+        }
+        @import url("theme.css");
+        """
+        media, import_rule = parse_stylesheet(css)
+
+        assert isinstance(media, AtRule)
+        assert serialize(media.prelude) == "screen"
+        supports = media.content[0]
+        assert serialize(supports.prelude) == "(display: grid)"
+        assert serialize(supports.content[0].prelude) == ".grid"
+        assert import_rule.name == "import"
+        assert import_rule.content is None
+
+    def test_nested_style_rules(self):
+        """Test nested rules, including ones that look like declarations."""
+        css = """
+        .card {
+            color: red;
+            a:hover { color: blue; }
+            & > .title { margin: 0 }
+            --slot: { padding: 0 };
+        }
+        """
+        (card,) = parse_stylesheet(css)
+        kinds = [type(node).__name__ for node in card.content]
+
+        assert kinds == ["Declaration", "QualifiedRule", "QualifiedRule", "Declaration"]
+        assert serialize(card.content[1].prelude) == "a:hover"
+        assert serialize(card.content[2].prelude) == "& > .title"
+        assert serialize(card.content[3].value) == "{ padding: 0 }"
+
+    def test_important(self):
+        """Test !important is split off the declaration value."""
+        (color, width) = parse_declarations("color: red ! IMPORTANT; width: 1px")
+
+        assert (serialize(color.value), color.important) == ("red", True)
+        assert (serialize(width.value), width.important) == ("1px", False)
+
+    def test_error_recovery(self):
+        """Test invalid declarations and unclosed blocks are recovered from."""
+        errors = []
+        css = ".a { 12px; color: red; } .b { width: 1px"
+        rules = parse_stylesheet(css, errors)
+
+        assert [serialize(rule.prelude) for rule in rules] == [".a", ".b"]
+        assert [d.name for d in rules[0].content] == ["color"]
+        assert [d.name for d in rules[1].content] == ["width"]
+        assert [e.message for e in sorted(errors, key=lambda e: e.offset)] == [
+            "Invalid declaration",
+            "Missing closing '}'",
+        ]
+
+    def test_rule_without_block(self):
+        """Test a trailing prelude without a block is dropped."""
+        errors = []
+        rules = parse_stylesheet(".a { color: red } .dangling", errors)
+
+        assert len(rules) == 1
+        assert errors[0].message == "Style rule without a block"
+
+    def test_offsets(self):
+        """Test rules and declarations record where they start."""
+        css = "@media print {\n  .a {\n    color: red;\n  }\n}"
+        (media,) = parse_stylesheet(css)
+        rule = media.content[0]
+
+        assert isinstance(rule, QualifiedRule)
+        assert isinstance(rule.content[0], Declaration)
+        assert (media.offset, rule.offset, rule.content[0].offset) == (0, 17, 26)
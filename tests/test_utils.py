@@ -10,9 +10,11 @@ from css_to_rust.utils import (
     format_css_property,
     group_related_selectors,
     is_valid_rust_identifier,
+    merge_use_statements,
     normalize_selector,
     optimize_css_content,
     sanitize_rust_identifier,
+    split_rust_items,
     validate_css_syntax,
 )
 
@@ -95,6 +97,30 @@ class TestRustIdentifier:
         assert sanitize_rust_identifier("type") == "type_style"
 
 
+class TestRustItems:
+    """Test splitting and merging generated Rust headers."""
+
+    def test_split_rust_items(self):
+        """Test use lines are split from the items after the doc comment."""
+        code = "//! Button styles\n\nuse stylist::Style;\n\npub fn button() {}"
+
+        assert split_rust_items(code) == (["use stylist::Style;"], "pub fn button() {}")
+
+    def test_merge_use_statements(self):
+        """Test imports from one path are grouped and deduplicated."""
+        statements = [
+            "use stylist::Style;",
+            "use yew::prelude::*;",
+            "use stylist::{css, StyleSource};",
+            "use stylist::Style;",
+        ]
+
+        assert merge_use_statements(statements) == [
+            "use stylist::{css, Style, StyleSource};",
+            "use yew::prelude::*;",
+        ]
+
+
 class TestFormatCssProperty:
     """Test format_css_property function."""
 